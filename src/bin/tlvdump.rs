@@ -0,0 +1,411 @@
+//! Print the structure of TLV encoded data
+//!
+//! Data is read from a file, standard input, or given directly as a hex or base64 string, and
+//! printed as a tree with offsets, types, lengths and critical flags.
+
+use std::fmt::Write as _;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use base64::Engine;
+use clap::{Parser, ValueEnum};
+use ndn_tlv::bytes::Bytes;
+use ndn_tlv::{
+    looks_nested, ndn_type, tlv_typ_critical, DecodeConfig, HexBytes, TlvDecodeRef, TlvError,
+    TlvNode, ValueKind, VarNum,
+};
+use serde_json::{json, Value};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Print the structure of TLV encoded data")]
+struct Args {
+    /// File to read, or `-` for standard input
+    #[arg(conflicts_with_all = ["hex", "base64"])]
+    file: Option<PathBuf>,
+
+    /// Decode the given hex string instead of reading a file
+    #[arg(long, conflicts_with = "base64")]
+    hex: Option<String>,
+
+    /// Decode the given base64 string instead of reading a file
+    #[arg(long)]
+    base64: Option<String>,
+
+    /// Encoding of the file or standard input
+    #[arg(long, value_enum, default_value_t = Encoding::Binary)]
+    encoding: Encoding,
+
+    /// Print type numbers only, without symbolic NDN names
+    #[arg(long)]
+    numeric: bool,
+
+    /// Only descend into known NDN container types
+    ///
+    /// By default, the values of other records are shown as nested records whenever they consist
+    /// entirely of valid TLV records.
+    #[arg(long)]
+    no_guess: bool,
+
+    /// Print the structure as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Encoding {
+    /// Raw TLV data
+    Binary,
+    /// Hex digits, ignoring whitespace and `:` separators
+    Hex,
+    /// Standard base64
+    Base64,
+}
+
+/// A parsed TLV record along with its location in the input
+#[derive(Debug)]
+struct Record {
+    offset: usize,
+    header_len: usize,
+    typ: u64,
+    value: Bytes,
+    children: Option<Vec<Record>>,
+}
+
+/// An error at a byte offset within the input
+#[derive(Debug)]
+struct DumpError {
+    offset: usize,
+    error: TlvError,
+}
+
+struct Dumper {
+    symbolic: bool,
+    guess: bool,
+}
+
+impl Dumper {
+    fn kind(&self, typ: u64) -> ValueKind {
+        match ndn_type(typ as usize) {
+            Some(info) if self.symbolic => info.kind,
+            _ => ValueKind::Unknown,
+        }
+    }
+
+    fn name(&self, typ: u64) -> Option<&'static str> {
+        ndn_type(typ as usize)
+            .filter(|_| self.symbolic)
+            .map(|info| info.name)
+    }
+
+    fn is_container(&self, typ: u64, value: &[u8]) -> bool {
+        match self.kind(typ) {
+            ValueKind::Container => true,
+            ValueKind::Unknown => self.guess && looks_nested(value),
+            ValueKind::NonNegativeInteger | ValueKind::Bytes => false,
+        }
+    }
+
+    /// Parse all records in `data`
+    ///
+    /// Records parsed before an error occurred are returned along with it.
+    fn parse(&self, data: Bytes) -> (Vec<Record>, Result<(), DumpError>) {
+        let (nodes, result) = TlvNode::parse_all_partial(
+            &mut data.clone(),
+            &DecodeConfig::default(),
+            &|typ, value| self.is_container(typ as u64, value),
+        );
+        let records = locate(&nodes, &data, 0);
+        let result = result.map_err(|error| DumpError {
+            offset: error.context().and_then(|c| c.offset).unwrap_or(0),
+            error: error.into_kind(),
+        });
+        (records, result)
+    }
+
+    fn write_tree(&self, out: &mut String, records: &[Record], depth: usize) {
+        for record in records {
+            let _ = write!(
+                out,
+                "{:>6}  {:indent$}{}",
+                record.offset,
+                "",
+                record.typ,
+                indent = depth * 2
+            );
+            if let Some(name) = self.name(record.typ) {
+                let _ = write!(out, " {name}");
+            }
+            let _ = write!(out, " len={}", record.value.len());
+            if tlv_typ_critical(record.typ as usize) {
+                out.push_str(" [critical]");
+            }
+            match &record.children {
+                Some(children) => {
+                    out.push('\n');
+                    self.write_tree(out, children, depth + 1);
+                }
+                None => {
+                    if !record.value.is_empty() {
+                        let value = HexBytes(&record.value);
+                        let _ = match self.integer(record) {
+                            Some(n) => write!(out, ": {value} = {n}"),
+                            None => write!(out, ": {value:#}"),
+                        };
+                    }
+                    out.push('\n');
+                }
+            }
+        }
+    }
+
+    fn integer(&self, record: &Record) -> Option<u64> {
+        if self.kind(record.typ) != ValueKind::NonNegativeInteger {
+            return None;
+        }
+        let value = &record.value[..];
+        match value.len() {
+            1 | 2 | 4 | 8 => Some(value.iter().fold(0, |n, &b| (n << 8) | u64::from(b))),
+            _ => None,
+        }
+    }
+
+    fn json(&self, records: &[Record]) -> Value {
+        records
+            .iter()
+            .map(|record| {
+                let mut obj = json!({
+                    "offset": record.offset,
+                    "type": record.typ,
+                    "length": record.value.len(),
+                    "header_length": record.header_len,
+                    "critical": tlv_typ_critical(record.typ as usize),
+                });
+                if let Some(name) = self.name(record.typ) {
+                    obj["name"] = json!(name);
+                }
+                match &record.children {
+                    Some(children) => obj["children"] = self.json(children),
+                    None => {
+                        obj["value"] = json!(HexBytes(&record.value).to_string());
+                        if let Some(n) = self.integer(record) {
+                            obj["integer"] = json!(n);
+                        }
+                    }
+                }
+                obj
+            })
+            .collect()
+    }
+}
+
+/// Attach the location in the input to `nodes`, which were parsed from `wire` at `offset`
+fn locate(nodes: &[TlvNode], wire: &Bytes, offset: usize) -> Vec<Record> {
+    let mut pos = 0;
+    let mut records = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        // The header was parsed before, but its encoding may not have been minimal
+        let mut header = &wire[pos..];
+        VarNum::decode_ref(&mut header).expect("type was parsed before");
+        let len = VarNum::decode_ref(&mut header).expect("length was parsed before");
+        let header_len = wire.len() - pos - header.len();
+        let value = wire.slice(pos + header_len..pos + header_len + usize::from(len));
+        let children = match node {
+            TlvNode::Nested { children, .. } => {
+                Some(locate(children, &value, offset + pos + header_len))
+            }
+            TlvNode::Leaf { .. } => None,
+        };
+        records.push(Record {
+            offset: offset + pos,
+            header_len,
+            typ: node.typ().value(),
+            value,
+            children,
+        });
+        pos += header_len + usize::from(len);
+    }
+    records
+}
+
+/// Parse hex digits, ignoring whitespace, `:` separators and a leading `0x`
+fn parse_hex(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    let text = text.strip_prefix("0x").unwrap_or(text);
+    let digits = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':')
+        .map(|c| c.to_digit(16).ok_or(format!("invalid hex digit '{c}'")))
+        .collect::<Result<Vec<_>, _>>()?;
+    if digits.len() % 2 != 0 {
+        return Err("odd number of hex digits".into());
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| (pair[0] * 16 + pair[1]) as u8)
+        .collect())
+}
+
+fn parse_base64(text: &str) -> Result<Vec<u8>, String> {
+    let text: String = text.split_whitespace().collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(text)
+        .map_err(|e| format!("invalid base64: {e}"))
+}
+
+fn read_input(args: &Args) -> Result<Vec<u8>, String> {
+    if let Some(text) = &args.hex {
+        return parse_hex(text);
+    }
+    if let Some(text) = &args.base64 {
+        return parse_base64(text);
+    }
+
+    let mut data = Vec::new();
+    match &args.file {
+        Some(path) if path.as_os_str() != "-" => {
+            data = std::fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?
+        }
+        _ => {
+            std::io::stdin()
+                .read_to_end(&mut data)
+                .map_err(|e| format!("stdin: {e}"))?;
+        }
+    }
+    match args.encoding {
+        Encoding::Binary => Ok(data),
+        Encoding::Hex => parse_hex(&String::from_utf8_lossy(&data)),
+        Encoding::Base64 => parse_base64(&String::from_utf8_lossy(&data)),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let data = match read_input(&args) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("tlvdump: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let dumper = Dumper {
+        symbolic: !args.numeric,
+        guess: !args.no_guess,
+    };
+    let (records, result) = dumper.parse(Bytes::from(data));
+
+    if args.json {
+        let mut output = json!({ "records": dumper.json(&records) });
+        if let Err(e) = &result {
+            output["error"] = json!({ "offset": e.offset, "message": e.error.to_string() });
+        }
+        println!("{output:#}");
+    } else {
+        let mut output = String::new();
+        dumper.write_tree(&mut output, &records, 0);
+        print!("{output}");
+    }
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("tlvdump: {} at byte {}", e.error, e.offset);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMPER: Dumper = Dumper {
+        symbolic: true,
+        guess: true,
+    };
+
+    fn dump(data: &'static [u8]) -> (String, Option<usize>) {
+        let (records, result) = DUMPER.parse(Bytes::from_static(data));
+        let mut output = String::new();
+        DUMPER.write_tree(&mut output, &records, 0);
+        (output, result.err().map(|e| e.offset))
+    }
+
+    #[test]
+    fn hex_input() {
+        assert_eq!(parse_hex("0x06 0a:ff\n"), Ok(vec![0x06, 0x0A, 0xFF]));
+        assert!(parse_hex("061").is_err());
+        assert!(parse_hex("0g").is_err());
+        assert_eq!(parse_base64("BgA=\n"), Ok(vec![6, 0]));
+    }
+
+    #[test]
+    fn tree() {
+        let (output, error) = dump(&[6, 12, 7, 3, 8, 1, b'a', 24, 1, 0, 200, 2, 201, 0]);
+        assert_eq!(error, None);
+        assert_eq!(
+            output,
+            "     0  6 Data len=12 [critical]\n\
+             \x20    2    7 Name len=3 [critical]\n\
+             \x20    4      8 GenericNameComponent len=1 [critical]: 61 \"a\"\n\
+             \x20    7    24 ContentType len=1 [critical]: 00 = 0\n\
+             \x20   10    200 len=2\n\
+             \x20   12      201 len=0 [critical]\n"
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let (output, error) = dump(&[7, 6, 8, 1, b'a', 8, 5, b'b']);
+        assert_eq!(error, Some(6));
+        assert_eq!(output.lines().count(), 2);
+    }
+
+    #[test]
+    fn non_minimal_header() {
+        let (records, result) = DUMPER.parse(Bytes::from_static(&[7, 0xFD, 0, 3, 8, 1, b'a']));
+        result.unwrap();
+        assert_eq!(records[0].header_len, 4);
+        let child = &records[0].children.as_ref().unwrap()[0];
+        assert_eq!((child.offset, child.header_len), (4, 2));
+    }
+
+    #[test]
+    fn depth_limit() {
+        let mut data = vec![8, 0];
+        for _ in 0..ndn_tlv::DEFAULT_MAX_NODE_DEPTH + 1 {
+            data.splice(0..0, [7, data.len() as u8]);
+        }
+        let (records, result) = DUMPER.parse(Bytes::from(data));
+        let error = result.unwrap_err();
+        assert!(matches!(error.error, TlvError::DepthLimitExceeded { .. }));
+        assert_eq!(records.len(), 1);
+    }
+
+    #[test]
+    fn json_output() {
+        let (records, result) = DUMPER.parse(Bytes::from_static(&[7, 3, 8, 1, b'a']));
+        result.unwrap();
+        assert_eq!(
+            DUMPER.json(&records),
+            json!([{
+                "offset": 0,
+                "type": 7,
+                "name": "Name",
+                "length": 3,
+                "header_length": 2,
+                "critical": true,
+                "children": [{
+                    "offset": 2,
+                    "type": 8,
+                    "name": "GenericNameComponent",
+                    "length": 1,
+                    "header_length": 2,
+                    "critical": true,
+                    "value": "61",
+                }],
+            }])
+        );
+    }
+}
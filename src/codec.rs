@@ -0,0 +1,280 @@
+use std::marker::PhantomData;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::reader::{extend_to_record, read_error, record_header, DEFAULT_MAX_RECORD_SIZE};
+use crate::tlv::check_type;
+use crate::{Result, Tlv, TlvDecode, TlvDecodeRef, TlvEncode, TlvError, VarNum};
+
+/// A [`Decoder`] and [`Encoder`] framing a byte stream into TLV records
+///
+/// The decoder waits until an entire record has arrived and then decodes it as `T`. With the
+/// default `T = Bytes`, each frame is the raw record, including its type and length.
+///
+/// Like [`TlvReader::next_tlv`](crate::TlvReader::next_tlv), a decoder for a type implementing
+/// [`Tlv`] checks the type of each record once its header has arrived. If it differs,
+/// [`TlvError::TypeMismatch`] is returned and the record is left in the buffer, so it can still
+/// be read with a `TlvCodec<Bytes>`.
+///
+/// Any type implementing [`TlvEncode`] can be encoded.
+#[derive(Debug)]
+pub struct TlvCodec<T = Bytes> {
+    max_record_size: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TlvCodec<T> {
+    /// Create a new `TlvCodec` using [`DEFAULT_MAX_RECORD_SIZE`]
+    pub fn new() -> Self {
+        Self {
+            max_record_size: DEFAULT_MAX_RECORD_SIZE,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set the maximum size of a single record, including type and length
+    pub fn with_max_record_size(mut self, max_record_size: usize) -> Self {
+        self.max_record_size = max_record_size;
+        self
+    }
+
+    /// The maximum size of a single record, including type and length
+    pub fn max_record_size(&self) -> usize {
+        self.max_record_size
+    }
+
+    /// Wait until the next record is completely in `src`, returning its total length
+    ///
+    /// If `expected_typ` is given, it is checked as soon as the header has arrived, so that a
+    /// record of a different type is neither buffered in full nor removed from `src`.
+    fn complete_record(
+        &self,
+        src: &mut BytesMut,
+        expected_typ: Option<usize>,
+    ) -> Result<Option<usize>> {
+        let Some((typ, record_len)) = record_header(src, self.max_record_size)? else {
+            return Ok(None);
+        };
+        if let Some(expected) = expected_typ {
+            check_type(typ, expected)?;
+        }
+        if src.len() < record_len {
+            src.reserve(record_len - src.len());
+            return Ok(None);
+        }
+        Ok(Some(record_len))
+    }
+}
+
+impl<T> Default for TlvCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for TlvCodec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            max_record_size: self.max_record_size,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl Decoder for TlvCodec<Bytes> {
+    type Item = Bytes;
+    type Error = TlvError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Bytes>> {
+        let Some(record_len) = self.complete_record(src, None)? else {
+            return Ok(None);
+        };
+        Ok(Some(src.split_to(record_len).freeze()))
+    }
+}
+
+impl<T: Tlv + TlvDecode> Decoder for TlvCodec<T> {
+    type Item = T;
+    type Error = TlvError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>> {
+        let Some(record_len) = self.complete_record(src, Some(T::TYP))? else {
+            return Ok(None);
+        };
+        T::decode(&mut src.split_to(record_len).freeze()).map(Some)
+    }
+}
+
+impl<T, I: TlvEncode> Encoder<I> for TlvCodec<T> {
+    type Error = TlvError;
+
+    fn encode(&mut self, item: I, dst: &mut BytesMut) -> Result<()> {
+        dst.reserve(item.size());
+        item.encode_to(dst);
+        Ok(())
+    }
+}
+
+/// Read a TLV from a type implementing `AsyncRead`
+///
+/// Asynchronous counterpart to [`Tlv::from_reader`]. Exactly one record is read, so `reader` can
+/// be used to read further data afterwards.
+pub async fn read_tlv_async<T>(mut reader: impl AsyncRead + Unpin) -> Result<T>
+where
+    T: Tlv + TlvDecode,
+{
+    let mut bytes = BytesMut::new();
+
+    let typ = read_varnum(&mut reader, &mut bytes).await?;
+    check_type(typ.into(), T::TYP)?;
+    read_varnum(&mut reader, &mut bytes).await?;
+
+    let header_len = extend_to_record(&mut bytes, DEFAULT_MAX_RECORD_SIZE)?;
+    read_exact(&mut reader, &mut bytes[header_len..]).await?;
+    T::decode(&mut bytes.freeze())
+}
+
+/// Write a TLV to a type implementing `AsyncWrite`
+///
+/// The value is encoded into a single buffer and written in one go.
+pub async fn write_tlv_async<T>(mut writer: impl AsyncWrite + Unpin, value: &T) -> Result<()>
+where
+    T: TlvEncode + ?Sized,
+{
+    writer.write_all(&value.encode()).await?;
+    Ok(())
+}
+
+async fn read_varnum(
+    reader: &mut (impl AsyncRead + Unpin),
+    bytes: &mut BytesMut,
+) -> Result<VarNum> {
+    let mut buf = [0; 9];
+    read_exact(reader, &mut buf[..1]).await?;
+    let len = VarNum::encoded_size(buf[0]);
+    read_exact(reader, &mut buf[1..len]).await?;
+    bytes.put(&buf[..len]);
+    VarNum::decode_ref(&mut &buf[..len])
+}
+
+async fn read_exact(reader: &mut (impl AsyncRead + Unpin), buf: &mut [u8]) -> Result<()> {
+    reader.read_exact(buf).await.map_err(read_error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::GenericNameComponent;
+
+    use super::*;
+
+    const RECORDS: [u8; 9] = [8, 5, b'h', b'e', b'l', b'l', b'o', 33, 0];
+
+    #[test]
+    fn decode_partial() {
+        let mut codec = TlvCodec::<Bytes>::new();
+        let mut buf = BytesMut::new();
+
+        buf.put(&RECORDS[..1]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        buf.put(&RECORDS[1..5]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        buf.put(&RECORDS[5..8]);
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), &RECORDS[..7]);
+        assert_eq!(codec.decode(&mut buf).unwrap(), None);
+        buf.put(&RECORDS[8..]);
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), &RECORDS[7..]);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn decode_typed() {
+        let mut codec = TlvCodec::<GenericNameComponent>::new();
+        let mut buf = BytesMut::from(&RECORDS[..]);
+
+        let component = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(component.name, &b"hello"[..]);
+        assert_eq!(
+            codec.decode(&mut buf).unwrap_err(),
+            TlvError::TypeMismatch {
+                expected: 8,
+                found: 33
+            }
+        );
+        assert_eq!(buf, &RECORDS[7..]);
+        assert_eq!(
+            TlvCodec::<Bytes>::new().decode(&mut buf).unwrap().unwrap(),
+            &RECORDS[7..]
+        );
+    }
+
+    #[test]
+    fn type_mismatch_before_content() {
+        let mut codec = TlvCodec::<GenericNameComponent>::new();
+        let mut buf = BytesMut::from(&[33, 0xFD, 0x10, 0x00][..]);
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap_err(),
+            TlvError::TypeMismatch {
+                expected: 8,
+                found: 33
+            }
+        );
+        assert_eq!(buf, &[33, 0xFD, 0x10, 0x00][..]);
+    }
+
+    #[test]
+    fn decode_too_large() {
+        let mut codec = TlvCodec::<Bytes>::new().with_max_record_size(4);
+        let mut buf = BytesMut::from(&RECORDS[..]);
+
+        assert_eq!(
+            codec.decode(&mut buf).unwrap_err(),
+            TlvError::RecordTooLarge { max: 4 }
+        );
+    }
+
+    #[test]
+    fn encode() {
+        let mut codec = TlvCodec::<Bytes>::new();
+        let mut buf = BytesMut::new();
+
+        codec
+            .encode(
+                GenericNameComponent {
+                    name: Bytes::from_static(b"hello"),
+                },
+                &mut buf,
+            )
+            .unwrap();
+        codec
+            .encode(Bytes::from_static(&RECORDS[7..]), &mut buf)
+            .unwrap();
+        assert_eq!(buf, &RECORDS[..]);
+    }
+
+    #[tokio::test]
+    async fn read_write_async() {
+        let (mut client, mut server) = tokio::io::duplex(4);
+        let component = GenericNameComponent {
+            name: Bytes::from_static(b"hello"),
+        };
+
+        let (written, read) = tokio::join!(
+            write_tlv_async(&mut client, &component),
+            read_tlv_async::<GenericNameComponent>(&mut server)
+        );
+        written.unwrap();
+        assert_eq!(read.unwrap(), component);
+
+        drop(client);
+        assert_eq!(
+            read_tlv_async::<GenericNameComponent>(&mut server)
+                .await
+                .unwrap_err(),
+            TlvError::UnexpectedEndOfStream
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use crate::{Result, TlvError};
+
+/// Configuration for decoding TLV data
+///
+/// Passed to [`TlvDecode::decode_with`](crate::TlvDecode::decode_with) and
+/// [`TlvDecodeRef::decode_ref_with`](crate::TlvDecodeRef::decode_ref_with). The default
+/// configuration matches the behaviour of [`TlvDecode::decode`](crate::TlvDecode::decode).
+///
+/// Types implementing `TlvDecode` through the `Tlv` derive macro use the default
+/// [`decode_with`](crate::TlvDecode::decode_with). For them, only the total length limit is
+/// checked, and only when they are decoded directly. Their fields are decoded with the default
+/// configuration, so strict mode and the other limits do not apply within them. The TLV-LENGTH
+/// and depth of records containing them are still checked by their enclosing decoder. The records
+/// of the packet format provided by this crate, such as [`Interest`](crate::Interest),
+/// [`MetaInfo`](crate::MetaInfo) or [`Nonce`](crate::Nonce), are implemented by hand and apply
+/// the configuration to all of their elements.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DecodeConfig {
+    strict: bool,
+    limits: DecodeLimits,
+    depth: usize,
+}
+
+impl DecodeConfig {
+    /// Create a new `DecodeConfig` with default settings
+    pub const fn new() -> Self {
+        Self {
+            strict: false,
+            limits: DecodeLimits::new(),
+            depth: 0,
+        }
+    }
+
+    /// Set whether non-minimal encodings should be rejected
+    ///
+    /// In strict mode, a [`VarNum`](crate::VarNum) or
+    /// [`NonNegativeInteger`](crate::NonNegativeInteger) that is not encoded using the smallest
+    /// possible representation leads to [`TlvError::NonMinimalEncoding`](crate::TlvError::NonMinimalEncoding).
+    /// This guarantees that two different byte strings never decode to the same value.
+    pub const fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Whether non-minimal encodings are rejected
+    pub const fn strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Set the resource limits to enforce while decoding
+    pub const fn with_limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// The resource limits enforced while decoding
+    pub const fn limits(&self) -> &DecodeLimits {
+        &self.limits
+    }
+
+    /// The number of TLV records enclosing the value currently being decoded
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The configuration to use for the content of a TLV record
+    ///
+    /// Fails with [`TlvError::DepthLimitExceeded`] if this would exceed the maximum depth.
+    pub fn nested(&self) -> Result<Self> {
+        let depth = self.depth + 1;
+        match self.limits.max_depth {
+            Some(max) if depth > max => Err(TlvError::DepthLimitExceeded { max }),
+            _ => Ok(Self { depth, ..*self }),
+        }
+    }
+
+    /// Check `len`, the size of the data to decode, against the total length limit
+    ///
+    /// Only the outermost value is checked, nested values are always smaller.
+    pub fn check_total_length(&self, len: usize) -> Result<()> {
+        match self.limits.max_total_length {
+            Some(max) if self.depth == 0 && len > max => Err(TlvError::LengthLimitExceeded { max }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check `len`, the declared TLV-LENGTH of a record, against the limit for single records
+    pub fn check_tlv_length(&self, len: u64) -> Result<()> {
+        match self.limits.max_tlv_length {
+            Some(max) if len > max as u64 => Err(TlvError::TlvLengthLimitExceeded { max }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check `count`, the number of elements decoded into a collection, against the element limit
+    pub fn check_elements(&self, count: usize) -> Result<()> {
+        match self.limits.max_elements {
+            Some(max) if count > max => Err(TlvError::ElementLimitExceeded { max }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Resource limits to protect decoders against hostile input
+///
+/// All limits are disabled by default. When decoding untrusted data, set limits appropriate for
+/// the expected input and pass them to the decoder using [`DecodeConfig::with_limits`]. Only the
+/// total length limit applies to types using the `Tlv` derive macro, see [`DecodeConfig`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DecodeLimits {
+    max_depth: Option<usize>,
+    max_total_length: Option<usize>,
+    max_elements: Option<usize>,
+    max_tlv_length: Option<usize>,
+}
+
+impl DecodeLimits {
+    /// Create a new `DecodeLimits` with all limits disabled
+    pub const fn new() -> Self {
+        Self {
+            max_depth: None,
+            max_total_length: None,
+            max_elements: None,
+            max_tlv_length: None,
+        }
+    }
+
+    /// Set the maximum number of nested TLV records
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Set the maximum size of the data passed to the outermost decoder
+    pub const fn with_max_total_length(mut self, max_total_length: usize) -> Self {
+        self.max_total_length = Some(max_total_length);
+        self
+    }
+
+    /// Set the maximum number of elements in a single `Vec`
+    pub const fn with_max_elements(mut self, max_elements: usize) -> Self {
+        self.max_elements = Some(max_elements);
+        self
+    }
+
+    /// Set the maximum TLV-LENGTH of a single TLV record
+    pub const fn with_max_tlv_length(mut self, max_tlv_length: usize) -> Self {
+        self.max_tlv_length = Some(max_tlv_length);
+        self
+    }
+
+    /// The maximum number of nested TLV records
+    pub const fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// The maximum size of the data passed to the outermost decoder
+    pub const fn max_total_length(&self) -> Option<usize> {
+        self.max_total_length
+    }
+
+    /// The maximum number of elements in a single `Vec`
+    pub const fn max_elements(&self) -> Option<usize> {
+        self.max_elements
+    }
+
+    /// The maximum TLV-LENGTH of a single TLV record
+    pub const fn max_tlv_length(&self) -> Option<usize> {
+        self.max_tlv_length
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use bytes::Bytes;
+
+    use crate::tests::GenericNameComponent;
+    use crate::{GenericTlv, Interest, MetaInfo, Tlv, TlvDecode, TlvDecodeRef, ValidityPeriod};
+
+    use super::*;
+
+    type Nested = GenericTlv<GenericTlv<GenericTlv<Bytes>>>;
+
+    const NESTED: [u8; 8] = [6, 6, 20, 4, 25, 2, 0x01, 0x00];
+
+    fn config(limits: DecodeLimits) -> DecodeConfig {
+        DecodeConfig::new().with_limits(limits)
+    }
+
+    #[test]
+    fn unlimited() {
+        let nested = Nested::decode_with(
+            &mut Bytes::from_static(&NESTED),
+            &config(DecodeLimits::new()),
+        );
+        assert_eq!(nested.unwrap().content.content.content, &[1, 0][..]);
+    }
+
+    #[test]
+    fn max_depth() {
+        let limits = DecodeLimits::new().with_max_depth(3);
+        assert!(Nested::decode_with(&mut Bytes::from_static(&NESTED), &config(limits)).is_ok());
+
+        let limits = DecodeLimits::new().with_max_depth(2);
+        let error =
+            Nested::decode_with(&mut Bytes::from_static(&NESTED), &config(limits)).unwrap_err();
+        assert_eq!(error, TlvError::DepthLimitExceeded { max: 2 });
+        assert_eq!(error.context().unwrap().offset, Some(6));
+    }
+
+    #[test]
+    fn max_total_length() {
+        let limits = DecodeLimits::new().with_max_total_length(8);
+        assert!(Nested::decode_with(&mut Bytes::from_static(&NESTED), &config(limits)).is_ok());
+
+        let limits = DecodeLimits::new().with_max_total_length(7);
+        assert_eq!(
+            Nested::decode_with(&mut Bytes::from_static(&NESTED), &config(limits)).unwrap_err(),
+            TlvError::LengthLimitExceeded { max: 7 }
+        );
+    }
+
+    #[test]
+    fn max_tlv_length() {
+        let limits = DecodeLimits::new().with_max_tlv_length(6);
+        assert!(Nested::decode_with(&mut Bytes::from_static(&NESTED), &config(limits)).is_ok());
+
+        let limits = DecodeLimits::new().with_max_tlv_length(5);
+        assert_eq!(
+            Nested::decode_with(&mut Bytes::from_static(&NESTED), &config(limits)).unwrap_err(),
+            TlvError::TlvLengthLimitExceeded { max: 5 }
+        );
+
+        // Checked before the content is looked at
+        let huge: &'static [u8] = &[8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert_eq!(
+            GenericTlv::<Bytes>::decode_with(&mut Bytes::from_static(huge), &config(limits))
+                .unwrap_err(),
+            TlvError::TlvLengthLimitExceeded { max: 5 }
+        );
+    }
+
+    #[test]
+    fn max_elements() {
+        let data: &'static [u8] = &[8, 1, b'a', 8, 1, b'b', 8, 1, b'c'];
+
+        let limits = DecodeLimits::new().with_max_elements(3);
+        let components =
+            Vec::<GenericTlv<Bytes>>::decode_with(&mut Bytes::from_static(data), &config(limits));
+        assert_eq!(components.unwrap().len(), 3);
+
+        let limits = DecodeLimits::new().with_max_elements(2);
+        let error =
+            Vec::<GenericTlv<Bytes>>::decode_with(&mut Bytes::from_static(data), &config(limits))
+                .unwrap_err();
+        assert_eq!(error, TlvError::ElementLimitExceeded { max: 2 });
+        assert_eq!(error.context().unwrap().offset, Some(6));
+    }
+
+    #[test]
+    fn default_decode_with() {
+        let data: &'static [u8] = &[8, 5, b'h', b'e', b'l', b'l', b'o'];
+
+        let limits = DecodeLimits::new().with_max_total_length(6);
+        assert_eq!(
+            GenericNameComponent::decode_with(&mut Bytes::from_static(data), &config(limits))
+                .unwrap_err(),
+            TlvError::LengthLimitExceeded { max: 6 }
+        );
+    }
+
+    #[test]
+    fn decode_ref_with() {
+        type NestedRef<'a> = GenericTlv<GenericTlv<GenericTlv<&'a [u8]>>>;
+
+        let limits = DecodeLimits::new().with_max_depth(3).with_max_tlv_length(6);
+        let nested = NestedRef::decode_ref_with(&mut &NESTED[..], &config(limits)).unwrap();
+        assert_eq!(nested.content.content.content, &[1, 0][..]);
+
+        let limits = DecodeLimits::new().with_max_depth(2);
+        assert_eq!(
+            NestedRef::decode_ref_with(&mut &NESTED[..], &config(limits)).unwrap_err(),
+            TlvError::DepthLimitExceeded { max: 2 }
+        );
+        let limits = DecodeLimits::new().with_max_tlv_length(5);
+        assert_eq!(
+            NestedRef::decode_ref_with(&mut &NESTED[..], &config(limits)).unwrap_err(),
+            TlvError::TlvLengthLimitExceeded { max: 5 }
+        );
+        let limits = DecodeLimits::new().with_max_total_length(7);
+        assert_eq!(
+            NestedRef::decode_ref_with(&mut &NESTED[..], &config(limits)).unwrap_err(),
+            TlvError::LengthLimitExceeded { max: 7 }
+        );
+
+        let data: &[u8] = &[8, 1, b'a', 8, 1, b'b', 8, 1, b'c'];
+        let limits = DecodeLimits::new().with_max_elements(2);
+        assert_eq!(
+            Vec::<GenericTlv<&[u8]>>::decode_ref_with(&mut &data[..], &config(limits)).unwrap_err(),
+            TlvError::ElementLimitExceeded { max: 2 }
+        );
+    }
+
+    #[test]
+    fn derived_types() {
+        #[derive(Debug, Tlv)]
+        #[tlv(7, internal = true)]
+        struct Derived {
+            components: Vec<GenericNameComponent>,
+        }
+
+        let data: &'static [u8] = &[7, 11, 8, 1, b'a', 8, 1, b'b', 8, 0xFD, 0, 1, b'c'];
+        let strict = DecodeConfig::new().with_strict(true).with_limits(
+            DecodeLimits::new()
+                .with_max_elements(2)
+                .with_max_depth(0)
+                .with_max_tlv_length(1),
+        );
+
+        // Not enforced for the fields of derived types, which is why the packet elements are not
+        // derived
+        let derived = Derived::decode_with(&mut Bytes::from_static(data), &strict).unwrap();
+        assert_eq!(derived.components.len(), 3);
+
+        let limits = DecodeLimits::new().with_max_total_length(12);
+        assert_eq!(
+            Derived::decode_with(&mut Bytes::from_static(data), &config(limits)).unwrap_err(),
+            TlvError::LengthLimitExceeded { max: 12 }
+        );
+    }
+
+    #[test]
+    fn packet_elements() {
+        // An InterestLifetime of 256 encoded in four bytes
+        let wire: &[u8] = &[5, 11, 7, 3, 8, 1, b'a', 12, 4, 0, 0, 1, 0];
+        let strict = DecodeConfig::new().with_strict(true);
+        Interest::decode(&mut Bytes::from_static(wire)).unwrap();
+        assert_eq!(
+            Interest::decode_with(&mut Bytes::from_static(wire), &strict).unwrap_err(),
+            TlvError::NonMinimalEncoding
+        );
+
+        let meta_info: &[u8] = &[20, 3, 25, 1, 5];
+        MetaInfo::decode_with(
+            &mut Bytes::from_static(meta_info),
+            &config(DecodeLimits::new()),
+        )
+        .unwrap();
+        let limits = DecodeLimits::new().with_max_depth(1);
+        assert_eq!(
+            MetaInfo::decode_with(&mut Bytes::from_static(meta_info), &config(limits)).unwrap_err(),
+            TlvError::DepthLimitExceeded { max: 1 }
+        );
+
+        // A NotBefore whose TLV-TYPE is encoded in five bytes
+        let validity: &[u8] = &[
+            0xFD, 0, 253, 12, 0xFE, 0, 0, 0, 254, 1, b'a', 0xFD, 0, 255, 1, b'b',
+        ];
+        ValidityPeriod::decode(&mut Bytes::from_static(validity)).unwrap();
+        assert_eq!(
+            ValidityPeriod::decode_with(&mut Bytes::from_static(validity), &strict).unwrap_err(),
+            TlvError::NonMinimalEncoding
+        );
+    }
+}
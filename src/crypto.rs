@@ -0,0 +1,867 @@
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+use hmac::{Hmac, Mac};
+use p256::ecdsa::signature;
+#[cfg(feature = "std")]
+use p256::ecdsa::signature::SignatureEncoding;
+use p256::pkcs8::{DecodePrivateKey, DecodePublicKey};
+#[cfg(feature = "std")]
+use rand_core::OsRng;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    ApplicationParameters, ComponentType, Data, Interest, InterestSignatureInfo,
+    InterestSignatureValue, KeyLocator, Name, NameComponent, Result, SignatureType, Signer,
+    TlvError, Verifier,
+};
+
+fn invalid_private_key(_: impl core::fmt::Debug) -> TlvError {
+    TlvError::InvalidKey {
+        reason: "invalid PKCS#8 private key",
+    }
+}
+
+fn invalid_public_key(_: impl core::fmt::Debug) -> TlvError {
+    TlvError::InvalidKey {
+        reason: "invalid SubjectPublicKeyInfo",
+    }
+}
+
+/// Signs and verifies with a plain SHA-256 digest, [`SignatureType::DigestSha256`]
+///
+/// A digest protects against corruption, but not against tampering, as anyone can compute it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DigestSha256;
+
+impl Signer for DigestSha256 {
+    fn signature_type(&self) -> SignatureType {
+        SignatureType::DigestSha256
+    }
+
+    fn sign(&self, signed_portion: &[u8]) -> Result<Bytes> {
+        Ok(Bytes::copy_from_slice(&Sha256::digest(signed_portion)))
+    }
+}
+
+impl Verifier for DigestSha256 {
+    fn signature_type(&self) -> SignatureType {
+        SignatureType::DigestSha256
+    }
+
+    fn verify(&self, signed_portion: &[u8], signature: &[u8]) -> bool {
+        signature == &Sha256::digest(signed_portion)[..]
+    }
+}
+
+/// Signs and verifies with an HMAC using SHA-256 and a shared key,
+/// [`SignatureType::HmacWithSha256`]
+#[derive(Clone)]
+pub struct HmacSha256 {
+    key: Vec<u8>,
+    key_locator: Option<KeyLocator>,
+}
+
+impl HmacSha256 {
+    /// Use the shared secret `key`
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: key.into(),
+            key_locator: None,
+        }
+    }
+
+    /// Set the key locator to put in the SignatureInfo
+    pub fn with_key_locator(mut self, key_locator: KeyLocator) -> Self {
+        self.key_locator = Some(key_locator);
+        self
+    }
+
+    fn mac(&self, signed_portion: &[u8]) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(signed_portion);
+        mac
+    }
+}
+
+impl Signer for HmacSha256 {
+    fn signature_type(&self) -> SignatureType {
+        SignatureType::HmacWithSha256
+    }
+
+    fn key_locator(&self) -> Option<KeyLocator> {
+        self.key_locator.clone()
+    }
+
+    fn sign(&self, signed_portion: &[u8]) -> Result<Bytes> {
+        let tag = self.mac(signed_portion).finalize().into_bytes();
+        Ok(Bytes::copy_from_slice(&tag))
+    }
+}
+
+impl Verifier for HmacSha256 {
+    fn signature_type(&self) -> SignatureType {
+        SignatureType::HmacWithSha256
+    }
+
+    fn verify(&self, signed_portion: &[u8], signature: &[u8]) -> bool {
+        self.mac(signed_portion).verify_slice(signature).is_ok()
+    }
+}
+
+/// Signs with an ECDSA key on the P-256 curve, [`SignatureType::Sha256WithEcdsa`]
+///
+/// Signatures are DER-encoded, as required by the packet format, and deterministic as described in
+/// RFC 6979.
+#[derive(Clone)]
+pub struct EcdsaP256Signer {
+    key: p256::ecdsa::SigningKey,
+    key_locator: Option<KeyLocator>,
+}
+
+impl EcdsaP256Signer {
+    /// Load the private key from its PKCS#8 DER encoding
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self> {
+        Ok(Self {
+            key: p256::ecdsa::SigningKey::from_pkcs8_der(der).map_err(invalid_private_key)?,
+            key_locator: None,
+        })
+    }
+
+    /// Set the key locator to put in the SignatureInfo
+    pub fn with_key_locator(mut self, key_locator: KeyLocator) -> Self {
+        self.key_locator = Some(key_locator);
+        self
+    }
+
+    /// A verifier for the public key
+    pub fn verifier(&self) -> EcdsaP256Verifier {
+        EcdsaP256Verifier {
+            key: *self.key.verifying_key(),
+        }
+    }
+}
+
+impl Signer for EcdsaP256Signer {
+    fn signature_type(&self) -> SignatureType {
+        SignatureType::Sha256WithEcdsa
+    }
+
+    fn key_locator(&self) -> Option<KeyLocator> {
+        self.key_locator.clone()
+    }
+
+    fn sign(&self, signed_portion: &[u8]) -> Result<Bytes> {
+        let signature: p256::ecdsa::Signature =
+            signature::Signer::try_sign(&self.key, signed_portion)
+                .map_err(|_| TlvError::SigningFailed)?;
+        Ok(Bytes::copy_from_slice(signature.to_der().as_bytes()))
+    }
+}
+
+/// Verifies [`SignatureType::Sha256WithEcdsa`] signatures with a public key on the P-256 curve
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EcdsaP256Verifier {
+    key: p256::ecdsa::VerifyingKey,
+}
+
+impl EcdsaP256Verifier {
+    /// Load the public key from its SubjectPublicKeyInfo DER encoding
+    pub fn from_public_key_der(der: &[u8]) -> Result<Self> {
+        Ok(Self {
+            key: p256::ecdsa::VerifyingKey::from_public_key_der(der).map_err(invalid_public_key)?,
+        })
+    }
+}
+
+impl Verifier for EcdsaP256Verifier {
+    fn signature_type(&self) -> SignatureType {
+        SignatureType::Sha256WithEcdsa
+    }
+
+    fn verify(&self, signed_portion: &[u8], signature: &[u8]) -> bool {
+        p256::ecdsa::Signature::from_der(signature).is_ok_and(|signature| {
+            signature::Verifier::verify(&self.key, signed_portion, &signature).is_ok()
+        })
+    }
+}
+
+/// Signs with an RSA key using PKCS #1 v1.5 padding, [`SignatureType::Sha256WithRsa`]
+///
+/// The private key operation is blinded with randomness from the operating system, which protects
+/// against timing attacks recovering the key (RUSTSEC-2023-0071). For this reason, signing with
+/// RSA requires the `std` feature, while [`RsaVerifier`] is always available.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct RsaSigner {
+    key: rsa::pkcs1v15::SigningKey<Sha256>,
+    key_locator: Option<KeyLocator>,
+}
+
+#[cfg(feature = "std")]
+impl RsaSigner {
+    /// Load the private key from its PKCS#8 DER encoding
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self> {
+        let key = rsa::RsaPrivateKey::from_pkcs8_der(der).map_err(invalid_private_key)?;
+        Ok(Self {
+            key: rsa::pkcs1v15::SigningKey::new(key),
+            key_locator: None,
+        })
+    }
+
+    /// Set the key locator to put in the SignatureInfo
+    pub fn with_key_locator(mut self, key_locator: KeyLocator) -> Self {
+        self.key_locator = Some(key_locator);
+        self
+    }
+
+    /// A verifier for the public key
+    pub fn verifier(&self) -> RsaVerifier {
+        RsaVerifier {
+            key: signature::Keypair::verifying_key(&self.key),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Signer for RsaSigner {
+    fn signature_type(&self) -> SignatureType {
+        SignatureType::Sha256WithRsa
+    }
+
+    fn key_locator(&self) -> Option<KeyLocator> {
+        self.key_locator.clone()
+    }
+
+    fn sign(&self, signed_portion: &[u8]) -> Result<Bytes> {
+        let signature =
+            signature::RandomizedSigner::try_sign_with_rng(&self.key, &mut OsRng, signed_portion)
+                .map_err(|_| TlvError::SigningFailed)?;
+        Ok(Bytes::from(signature.to_vec()))
+    }
+}
+
+/// Verifies [`SignatureType::Sha256WithRsa`] signatures with an RSA public key
+#[derive(Debug, Clone)]
+pub struct RsaVerifier {
+    key: rsa::pkcs1v15::VerifyingKey<Sha256>,
+}
+
+impl RsaVerifier {
+    /// Load the public key from its SubjectPublicKeyInfo DER encoding
+    pub fn from_public_key_der(der: &[u8]) -> Result<Self> {
+        let key = rsa::RsaPublicKey::from_public_key_der(der).map_err(invalid_public_key)?;
+        Ok(Self {
+            key: rsa::pkcs1v15::VerifyingKey::new(key),
+        })
+    }
+}
+
+impl PartialEq for RsaVerifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.key.as_ref() == other.key.as_ref()
+    }
+}
+
+impl Eq for RsaVerifier {}
+
+impl Verifier for RsaVerifier {
+    fn signature_type(&self) -> SignatureType {
+        SignatureType::Sha256WithRsa
+    }
+
+    fn verify(&self, signed_portion: &[u8], signature: &[u8]) -> bool {
+        rsa::pkcs1v15::Signature::try_from(signature).is_ok_and(|signature| {
+            signature::Verifier::verify(&self.key, signed_portion, &signature).is_ok()
+        })
+    }
+}
+
+/// Signs with an Ed25519 key, [`SignatureType::Ed25519`]
+#[derive(Clone)]
+pub struct Ed25519Signer {
+    key: ed25519_dalek::SigningKey,
+    key_locator: Option<KeyLocator>,
+}
+
+impl Ed25519Signer {
+    /// Load the private key from its PKCS#8 DER encoding
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self> {
+        Ok(Self {
+            key: ed25519_dalek::SigningKey::from_pkcs8_der(der).map_err(invalid_private_key)?,
+            key_locator: None,
+        })
+    }
+
+    /// Set the key locator to put in the SignatureInfo
+    pub fn with_key_locator(mut self, key_locator: KeyLocator) -> Self {
+        self.key_locator = Some(key_locator);
+        self
+    }
+
+    /// A verifier for the public key
+    pub fn verifier(&self) -> Ed25519Verifier {
+        Ed25519Verifier {
+            key: self.key.verifying_key(),
+        }
+    }
+}
+
+impl Signer for Ed25519Signer {
+    fn signature_type(&self) -> SignatureType {
+        SignatureType::Ed25519
+    }
+
+    fn key_locator(&self) -> Option<KeyLocator> {
+        self.key_locator.clone()
+    }
+
+    fn sign(&self, signed_portion: &[u8]) -> Result<Bytes> {
+        let signature = signature::Signer::try_sign(&self.key, signed_portion)
+            .map_err(|_| TlvError::SigningFailed)?;
+        Ok(Bytes::copy_from_slice(&signature.to_bytes()))
+    }
+}
+
+/// Verifies [`SignatureType::Ed25519`] signatures with an Ed25519 public key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ed25519Verifier {
+    key: ed25519_dalek::VerifyingKey,
+}
+
+impl Ed25519Verifier {
+    /// Load the public key from its SubjectPublicKeyInfo DER encoding
+    pub fn from_public_key_der(der: &[u8]) -> Result<Self> {
+        Ok(Self {
+            key: ed25519_dalek::VerifyingKey::from_public_key_der(der)
+                .map_err(invalid_public_key)?,
+        })
+    }
+}
+
+impl Verifier for Ed25519Verifier {
+    fn signature_type(&self) -> SignatureType {
+        SignatureType::Ed25519
+    }
+
+    fn verify(&self, signed_portion: &[u8], signature: &[u8]) -> bool {
+        ed25519_dalek::Signature::from_slice(signature).is_ok_and(|signature| {
+            signature::Verifier::verify(&self.key, signed_portion, &signature).is_ok()
+        })
+    }
+}
+
+impl Data {
+    /// Sign the Data with a plain SHA-256 digest of its signed portion
+    ///
+    /// This is [`sign`](Data::sign) with [`DigestSha256`], which cannot fail.
+    pub fn sign_digest_sha256(&mut self) {
+        self.sign(&DigestSha256)
+            .expect("computing a digest cannot fail");
+    }
+
+    /// Check that the Data carries a valid [`SignatureType::DigestSha256`] signature
+    ///
+    /// This is [`verify`](Data::verify) with [`DigestSha256`].
+    pub fn verify_digest_sha256(&self) -> bool {
+        self.verify(&DigestSha256)
+    }
+}
+
+impl Interest {
+    /// The SHA-256 digest of the parameters and signature, or `None` if there are no
+    /// ApplicationParameters
+    fn parameters_digest(&self) -> Option<[u8; 32]> {
+        self.application_parameters
+            .as_ref()
+            .map(|_| Sha256::digest(self.parameters_portion()).into())
+    }
+
+    /// Make the ParametersSha256DigestComponent match the parameters and signature
+    ///
+    /// Any existing digest components are removed. If the Interest has ApplicationParameters, a
+    /// digest over them and the following elements is appended as the last name component. This
+    /// must be called after changing any of these elements, which [`sign`](Interest::sign) does.
+    /// The Interest is re-encoded from then on, so the digest is computed over the re-encoding.
+    pub fn update_parameters_digest(&mut self) {
+        self.wire = None;
+        let mut name: Name = self
+            .name
+            .iter()
+            .filter(|c| c.component_type() != ComponentType::ParametersSha256Digest)
+            .cloned()
+            .collect();
+        if let Some(digest) = self.parameters_digest() {
+            name.push(NameComponent::parameters_sha256_digest(digest));
+        }
+        self.name = name;
+    }
+
+    /// Check the ParametersSha256DigestComponent
+    ///
+    /// An Interest with ApplicationParameters must have exactly one digest component, which matches
+    /// the parameters and the following elements. An Interest without them must have none.
+    pub fn verify_parameters_digest(&self) -> bool {
+        let mut digests = self
+            .name
+            .iter()
+            .filter_map(NameComponent::as_parameters_sha256_digest);
+        match (self.parameters_digest(), digests.next(), digests.next()) {
+            (Some(expected), Some(found), None) => &expected == found,
+            (None, None, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Sign the Interest with `signer`, as a signed Interest of the packet format v0.3
+    ///
+    /// This sets the signature type and key locator of the InterestSignatureInfo from `signer`,
+    /// keeping its nonce, time and sequence number if one was present. Without an
+    /// InterestSignatureInfo, one is created; with the `std` feature, it is given a random
+    /// [`SignatureNonce`](crate::SignatureNonce) and the current
+    /// [`SignatureTime`](crate::SignatureTime). Empty ApplicationParameters are added if there are
+    /// none, as the signature requires them. Finally, the InterestSignatureValue is filled with the
+    /// signature of [`signed_portion`](Interest::signed_portion) and the parameters digest is
+    /// updated. The signed Interest is re-encoded rather than using the bytes it was decoded from.
+    ///
+    /// If `signer` fails, the Interest is left unchanged.
+    pub fn sign(&mut self, signer: &(impl Signer + ?Sized)) -> Result<()> {
+        let mut signed = Interest {
+            wire: None,
+            ..self.clone()
+        };
+        let mut info = signed
+            .signature_info
+            .take()
+            .unwrap_or_else(|| new_signature_info(signer.signature_type()));
+        info.signature_type = signer.signature_type();
+        info.key_locator = signer.key_locator();
+        signed.signature_info = Some(info);
+        signed
+            .application_parameters
+            .get_or_insert_with(|| ApplicationParameters(Bytes::new()));
+        let signature = signer.sign(&signed.signed_portion())?;
+        signed.signature_value = Some(InterestSignatureValue(signature));
+        signed.update_parameters_digest();
+        *self = signed;
+        Ok(())
+    }
+
+    /// Check the signature and parameters digest of the Interest with `verifier`
+    ///
+    /// For a decoded Interest that has not been modified, both are checked against the bytes it was
+    /// decoded from, so non-minimal encodings and unknown elements are covered as received.
+    /// Returns `false` if the signature is missing, its type does not match `verifier`, or the
+    /// digest component is wrong.
+    pub fn verify(&self, verifier: &(impl Verifier + ?Sized)) -> bool {
+        let (Some(info), Some(value)) = (&self.signature_info, &self.signature_value) else {
+            return false;
+        };
+        info.signature_type == verifier.signature_type()
+            && self.verify_parameters_digest()
+            && verifier.verify(&self.signed_portion(), &value.0)
+    }
+}
+
+/// The InterestSignatureInfo created by [`Interest::sign`] if none was set
+fn new_signature_info(signature_type: SignatureType) -> InterestSignatureInfo {
+    let info = InterestSignatureInfo::new(signature_type);
+    #[cfg(feature = "std")]
+    let info = {
+        let mut nonce = [0; 8];
+        getrandom::getrandom(&mut nonce).expect("failed to generate random nonce");
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        info.with_nonce(Bytes::copy_from_slice(&nonce))
+            .with_time(now)
+    };
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use bytes::Bytes;
+
+    use super::*;
+    use crate::tests::Failing;
+    use crate::{Name, SignatureInfo, SignatureValue, TlvDecode, TlvEncode};
+
+    const MESSAGE: &[u8] = b"/ndn/test signed portion";
+
+    // Generated with OpenSSL, which also created the signatures of MESSAGE
+    const EC_PRIVATE: &str = "
+        308187020100301306072a8648ce3d020106082a8648ce3d030107046d306b0201010420b2fab86aa9ea3cfd
+        116e9b57205a7985e2ffe7a78edc044f056cda46a0afa75da1440342000443f7e6a09a8b73f69339718af622
+        4d81840c4905d23673d18efa2d0b8a18d48baf259726cbd572c589de828c28f01629dace92d86b5a383b9a46
+        9a33f9cb28a1
+    ";
+    const EC_PUBLIC: &str = "
+        3059301306072a8648ce3d020106082a8648ce3d0301070342000443f7e6a09a8b73f69339718af6224d8184
+        0c4905d23673d18efa2d0b8a18d48baf259726cbd572c589de828c28f01629dace92d86b5a383b9a469a33f9
+        cb28a1
+    ";
+    const EC_SIGNATURE: &str = "
+        3045022100c0d404550a1cf5d8420f82a468053e27a5a9854e2398b62991844556f50903d402207d5dc8604f
+        d88b322ba6f04e1f9bb59c55db738523f9b6805e7e591832885aa3
+    ";
+    #[cfg(feature = "std")]
+    const RSA_PRIVATE: &str = "
+        30820275020100300d06092a864886f70d01010105000482025f3082025b02010002818100e5598bd8f90252
+        514aae2f346c932d0a5f957ae44d7a35d778b06517f54a81a046278101a4b6d9dd1cbab5c47090735480102f
+        5500478676800b96cfb6af73065078cc933a21fa8f13a9d59ea3518ef29403537bed1e5b8fe51557e9775ea2
+        402143750c25246acbe0870d416493f55171de4dba9c7887dc13929fc11e36fdc10203010001028180669c36
+        963f6a0b7c354652f648715c605cbd5afb13b2ceadf5602e0a9702db95e26d402985110dce0051848b3bb554
+        f4044bb971752b8afc2f737fe8b49a30825496db8ea35ad017d1cb3edd9b965a3238c8e7272b928eb401ea11
+        a0b9385e9b461060ede136eaee8b1a9b871db28d57c80a01991756914c0e24d7f0819a9af5024100ff516ea4
+        55761655afaa3846d97b4ee61f94a58d00fac2b482553f9066ca0f2ba31c983d76684298bbd8266761f593b8
+        ce6110f6c3c4037d168a55de17bdbd1b024100e5f65bde4be6f60e54a1a7b9ad01f7c94e27a0a723953a592a
+        c4532b8aff0199758e0b7d4cd86d32475386e468251de6feda063696d0d13722f3a5df7c08ea5302406790c8
+        a8b061ee3d63bc5087acc07008b8d6bb30af2d08bde000648056b1afe5ab8c75d4b8ab22188259f37a0c2a69
+        79957d9a3bfebd0c0d398f73b55b3c03f102406df7fdbeb435627d3fe37e3dc08a2c604bcb8f7bddfc42c6d9
+        38c13d155f14f8b9ca8f9bffdf8f7877bb6dca54334bb5673203be9f2884a40546d68fa4d4ee9902401448ce
+        e3cfe7170f7705919837b7a648e4cd97d55ff7e254a83545862e800741a6d8fefe1c3dfb961828d635f19156
+        222225058ecab026dd2f5b5fbf597d6c21
+    ";
+    const RSA_PUBLIC: &str = "
+        30819f300d06092a864886f70d010101050003818d0030818902818100e5598bd8f90252514aae2f346c932d
+        0a5f957ae44d7a35d778b06517f54a81a046278101a4b6d9dd1cbab5c47090735480102f5500478676800b96
+        cfb6af73065078cc933a21fa8f13a9d59ea3518ef29403537bed1e5b8fe51557e9775ea2402143750c25246a
+        cbe0870d416493f55171de4dba9c7887dc13929fc11e36fdc10203010001
+    ";
+    const RSA_SIGNATURE: &str = "
+        c57409723f1215c5ee47956d239531bb256efc95b0508dcb3af105c4e46d39688dd6d2c6e32ee002c0e9642b
+        455c62a8d76252d8d7aab5f064558df7373aec775c23f83e8198e7b12102f6286e3f63cd29d18a8d0b578b66
+        6390ecb5c530459e8c9d7b39bfd4e752716354ed25fdc13a48063dac016377c97022e988a688ee14
+    ";
+    const ED25519_PRIVATE: &str = "
+        302e020100300506032b657004220420d8f38f35fc2944f73284814d348b8fa0f4745780285bc14c0cae30a1
+        8fbdb237
+    ";
+    const ED25519_PUBLIC: &str = "
+        302a300506032b65700321005a4ece19852869ad0d05fdaed987170e0197b19fe6aa70b898b51dedd0e9b035
+    ";
+    const ED25519_SIGNATURE: &str = "
+        e83c903b48ed2960d3d097f7057b472b15a4e347738867f260d542aded4ab3dccf8904171d44ec48e66f658e
+        51cfcafaaaa78feb02f439ef55ba8ac428a9cc05
+    ";
+    const HMAC_SIGNATURE: &str = "
+        4a65779897efad930cd7a1e6a236929636d7b2f1bf11f58bf113810c47f7be6f
+    ";
+
+    fn hex(hex: &str) -> Vec<u8> {
+        crate::hex::decode(hex).unwrap()
+    }
+
+    const DIGEST: [u8; 32] = [
+        0xDC, 0x21, 0x13, 0x59, 0x5A, 0xAD, 0x08, 0x09, 0x9F, 0x80, 0xBD, 0x11, 0xA0, 0x13, 0xA0,
+        0x77, 0xF1, 0xAE, 0x45, 0xCC, 0x52, 0xD4, 0xA8, 0xE9, 0xCD, 0x08, 0xFD, 0xE6, 0x31, 0xE0,
+        0x1A, 0xAE,
+    ];
+
+    #[test]
+    fn sign_verify() {
+        let mut data = Data::new("/a".parse().unwrap()).with_content(&b"hi"[..]);
+        assert!(!data.verify_digest_sha256());
+
+        data.sign_digest_sha256();
+        assert_eq!(data.signature_value().unwrap().0, &DIGEST[..]);
+        assert!(data.verify_digest_sha256());
+
+        let mut wire = data.encode().to_vec();
+        assert_eq!(
+            wire[..18],
+            [6, 48, 7, 3, 8, 1, b'a', 21, 2, b'h', b'i', 22, 3, 27, 1, 0, 23, 32]
+        );
+        let decoded = Data::decode(&mut Bytes::from(wire.clone())).unwrap();
+        assert!(decoded.verify_digest_sha256());
+
+        wire[9] = b'H';
+        let tampered = Data::decode(&mut Bytes::from(wire)).unwrap();
+        assert!(!tampered.verify_digest_sha256());
+    }
+
+    #[test]
+    fn verify_original_wire() {
+        // The unknown element is covered by the digest, but dropped when re-encoding
+        let signed: &[u8] = &[7, 3, 8, 1, b'a', 200, 1, 0, 22, 3, 27, 1, 0];
+        let mut wire = Vec::from([6, 47]);
+        wire.extend_from_slice(signed);
+        wire.extend_from_slice(&[23, 32]);
+        wire.extend_from_slice(&Sha256::digest(signed));
+
+        let data = Data::decode(&mut Bytes::from(wire)).unwrap();
+        assert!(data.verify_digest_sha256());
+
+        let mut reencoded = Data::new(data.name().clone());
+        reencoded.set_signature_info(data.signature_info().cloned());
+        reencoded.set_signature_value(data.signature_value().cloned());
+        assert!(!reencoded.verify_digest_sha256());
+    }
+
+    #[test]
+    fn other_signature_type() {
+        let mut data = Data::new("/a".parse().unwrap()).with_content(&b"hi"[..]);
+        data.set_signature_info(Some(SignatureInfo::new(SignatureType::Other(200))));
+        let digest = Sha256::digest(data.signed_portion());
+        data.set_signature_value(Some(SignatureValue(Bytes::copy_from_slice(&digest))));
+        assert!(!data.verify_digest_sha256());
+    }
+
+    #[test]
+    fn hmac_sha256() {
+        let key = HmacSha256::new((0..16).collect::<Vec<u8>>());
+        assert_eq!(key.sign(MESSAGE).unwrap(), hex(HMAC_SIGNATURE));
+        assert!(key.verify(MESSAGE, &hex(HMAC_SIGNATURE)));
+        assert!(!key.verify(b"other", &hex(HMAC_SIGNATURE)));
+        assert!(!HmacSha256::new(&b"other key"[..]).verify(MESSAGE, &hex(HMAC_SIGNATURE)));
+    }
+
+    #[test]
+    fn ecdsa_p256() {
+        let signer = EcdsaP256Signer::from_pkcs8_der(&hex(EC_PRIVATE)).unwrap();
+        let verifier = EcdsaP256Verifier::from_public_key_der(&hex(EC_PUBLIC)).unwrap();
+        assert_eq!(signer.verifier(), verifier);
+        assert!(verifier.verify(MESSAGE, &hex(EC_SIGNATURE)));
+
+        let signature = signer.sign(MESSAGE).unwrap();
+        assert_eq!(signature[0], 0x30, "signatures are DER-encoded");
+        assert_eq!(signer.sign(MESSAGE).unwrap(), signature);
+        assert!(verifier.verify(MESSAGE, &signature));
+        assert!(!verifier.verify(b"other", &signature));
+        assert!(!verifier.verify(MESSAGE, &signature[..signature.len() - 1]));
+    }
+
+    #[test]
+    fn rsa() {
+        let verifier = RsaVerifier::from_public_key_der(&hex(RSA_PUBLIC)).unwrap();
+        #[cfg(feature = "std")]
+        {
+            let signer = RsaSigner::from_pkcs8_der(&hex(RSA_PRIVATE)).unwrap();
+            assert_eq!(signer.verifier(), verifier);
+            // Blinding does not change the deterministic PKCS #1 v1.5 signature
+            assert_eq!(signer.sign(MESSAGE).unwrap(), hex(RSA_SIGNATURE));
+        }
+        assert!(verifier.verify(MESSAGE, &hex(RSA_SIGNATURE)));
+        assert!(!verifier.verify(b"other", &hex(RSA_SIGNATURE)));
+    }
+
+    #[test]
+    fn ed25519() {
+        let signer = Ed25519Signer::from_pkcs8_der(&hex(ED25519_PRIVATE)).unwrap();
+        let verifier = Ed25519Verifier::from_public_key_der(&hex(ED25519_PUBLIC)).unwrap();
+        assert_eq!(signer.verifier(), verifier);
+        assert_eq!(signer.sign(MESSAGE).unwrap(), hex(ED25519_SIGNATURE));
+        assert!(verifier.verify(MESSAGE, &hex(ED25519_SIGNATURE)));
+        assert!(!verifier.verify(b"other", &hex(ED25519_SIGNATURE)));
+    }
+
+    #[test]
+    fn invalid_keys() {
+        let private = TlvError::InvalidKey {
+            reason: "invalid PKCS#8 private key",
+        };
+        let public = TlvError::InvalidKey {
+            reason: "invalid SubjectPublicKeyInfo",
+        };
+        assert_eq!(
+            EcdsaP256Signer::from_pkcs8_der(&hex(ED25519_PRIVATE)).err(),
+            Some(private)
+        );
+        assert_eq!(
+            RsaVerifier::from_public_key_der(&hex(EC_PUBLIC)).unwrap_err(),
+            public
+        );
+        assert_eq!(
+            Ed25519Verifier::from_public_key_der(&hex(ED25519_PRIVATE)).unwrap_err(),
+            public
+        );
+    }
+
+    #[test]
+    fn sign_data() {
+        let key_name: Name = "/key".parse().unwrap();
+        let locator = KeyLocator::Name(key_name.clone());
+        let ecdsa = EcdsaP256Signer::from_pkcs8_der(&hex(EC_PRIVATE)).unwrap();
+        let ed25519 = Ed25519Signer::from_pkcs8_der(&hex(ED25519_PRIVATE)).unwrap();
+        let hmac = HmacSha256::new(&b"secret"[..]);
+        let signers = (
+            hmac.clone().with_key_locator(locator.clone()),
+            ecdsa.clone().with_key_locator(locator.clone()),
+            ed25519.clone().with_key_locator(locator.clone()),
+        );
+        let verifiers = (ecdsa.verifier(), ed25519.verifier());
+        #[cfg_attr(not(feature = "std"), allow(unused_mut))]
+        let mut keys: Vec<(&dyn Signer, &dyn Verifier)> = alloc::vec![
+            (&DigestSha256, &DigestSha256),
+            (&signers.0, &hmac),
+            (&signers.1, &verifiers.0),
+            (&signers.2, &verifiers.1),
+        ];
+        #[cfg(feature = "std")]
+        let rsa = RsaSigner::from_pkcs8_der(&hex(RSA_PRIVATE)).unwrap();
+        #[cfg(feature = "std")]
+        let rsa = (
+            rsa.clone().with_key_locator(locator.clone()),
+            rsa.verifier(),
+        );
+        #[cfg(feature = "std")]
+        keys.push((&rsa.0, &rsa.1));
+
+        for &(signer, verifier) in &keys {
+            let mut data = Data::new("/a".parse().unwrap()).with_content(&b"hi"[..]);
+            data.sign(signer).unwrap();
+            let info = data.signature_info().unwrap();
+            assert_eq!(info.signature_type, signer.signature_type());
+            assert_eq!(info.key_locator, signer.key_locator());
+
+            let decoded = Data::decode(&mut data.encode()).unwrap();
+            assert!(decoded.verify(verifier));
+            for &(_, other) in &keys {
+                assert_eq!(
+                    decoded.verify(other),
+                    other.signature_type() == verifier.signature_type()
+                );
+            }
+
+            let mut tampered = decoded.clone();
+            tampered.set_content(Some(crate::Content(Bytes::from_static(b"ho"))));
+            assert!(!tampered.verify(verifier));
+        }
+    }
+
+    #[test]
+    fn signed_interest() {
+        // Computed independently with Python's hashlib
+        const WIRE: &str = "
+            055c07250801610220de4f0e6904a25caf4aa0702429e846ad5f851d24ad1ecdf87c9388ae4b1f46d90a
+            040102030424030102032c061b01002a01012e201f78a014d5cbf448a3ee71f710fe4441e893662baaae
+            64c1a298b1da5a27e85e
+        ";
+        let mut interest = Interest {
+            nonce: Some(crate::Nonce([1, 2, 3, 4])),
+            ..Interest::new("/a".parse().unwrap())
+                .with_application_parameters(&[1, 2, 3][..])
+                .with_signature_info(
+                    InterestSignatureInfo::new(SignatureType::DigestSha256).with_seq_num(1),
+                )
+        };
+        interest.sign(&DigestSha256).unwrap();
+        assert_eq!(interest.encode(), hex(WIRE));
+        assert!(interest.verify_parameters_digest());
+        assert!(interest.verify(&DigestSha256));
+
+        // Signing again replaces the digest component instead of adding another
+        interest.sign(&HmacSha256::new(&b"secret"[..])).unwrap();
+        assert_eq!(interest.name.len(), 2);
+        let decoded = Interest::decode(&mut interest.encode()).unwrap();
+        assert!(decoded.verify(&HmacSha256::new(&b"secret"[..])));
+        assert!(!decoded.verify(&DigestSha256));
+        assert!(!decoded.verify(&HmacSha256::new(&b"other"[..])));
+
+        let mut tampered = decoded.clone();
+        tampered.application_parameters = Some(ApplicationParameters(Bytes::from_static(b"x")));
+        assert!(!tampered.verify_parameters_digest());
+        tampered.update_parameters_digest();
+        assert!(tampered.verify_parameters_digest());
+        assert!(!tampered.verify(&HmacSha256::new(&b"secret"[..])));
+    }
+
+    #[test]
+    fn non_minimal_signed_interest() {
+        // ApplicationParameters with a three-byte length and a two-byte SignatureType
+        let parameters: &[u8] = &[36, 0xfd, 0, 3, 1, 2, 3, 44, 4, 27, 2, 0, 0];
+        let mut signed = alloc::vec![8, 1, b'a'];
+        signed.extend_from_slice(parameters);
+        let mut portion = parameters.to_vec();
+        portion.extend_from_slice(&[46, 32]);
+        portion.extend_from_slice(&Sha256::digest(&signed));
+        let mut name = alloc::vec![7, 37, 8, 1, b'a', 2, 32];
+        name.extend_from_slice(&Sha256::digest(&portion));
+        let mut wire = alloc::vec![5, (name.len() + portion.len()) as u8];
+        wire.extend_from_slice(&name);
+        wire.extend_from_slice(&portion);
+
+        let decoded = Interest::decode(&mut Bytes::from(wire.clone())).unwrap();
+        assert_eq!(decoded.signed_portion(), signed);
+        assert!(decoded.verify(&DigestSha256));
+        assert_eq!(decoded.encode(), wire);
+        assert_eq!(decoded.size(), wire.len());
+
+        // Once modified, the Interest is re-encoded minimally
+        let mut modified = decoded.clone();
+        modified.hop_limit = Some(crate::HopLimit(1));
+        assert_ne!(modified.signed_portion(), signed);
+        assert!(!modified.verify(&DigestSha256));
+        modified.hop_limit = None;
+        assert!(modified.verify(&DigestSha256));
+
+        // Signing does not keep the original encoding
+        let mut resigned = decoded.clone();
+        resigned.sign(&DigestSha256).unwrap();
+        assert!(resigned.wire.is_none());
+        let decoded = Interest::decode(&mut resigned.encode()).unwrap();
+        assert!(decoded.verify(&DigestSha256));
+    }
+
+    #[test]
+    fn failed_interest_signing() {
+        let interest = Interest {
+            nonce: Some(crate::Nonce([1, 2, 3, 4])),
+            ..Interest::new("/a".parse().unwrap())
+        };
+        let mut signed = interest.clone();
+        assert_eq!(signed.sign(&Failing), Err(TlvError::SigningFailed));
+        assert_eq!(signed, interest);
+        assert!(signed.signature_info.is_none());
+        assert!(signed.application_parameters.is_none());
+    }
+
+    #[test]
+    fn parameters_digest() {
+        let mut interest = Interest::new("/a".parse().unwrap());
+        assert!(interest.verify_parameters_digest());
+        interest.update_parameters_digest();
+        assert_eq!(interest.name.len(), 1);
+
+        interest.application_parameters = Some(ApplicationParameters(Bytes::new()));
+        assert!(!interest.verify_parameters_digest());
+        interest.update_parameters_digest();
+        assert!(interest.verify_parameters_digest());
+
+        // Only one digest component is allowed
+        interest.name.push(interest.name.get(1).unwrap().clone());
+        assert!(!interest.verify_parameters_digest());
+
+        interest.application_parameters = None;
+        assert!(!interest.verify_parameters_digest());
+        interest.update_parameters_digest();
+        assert_eq!(interest.name.len(), 1);
+        assert!(interest.verify_parameters_digest());
+
+        // Unknown elements after the parameters are covered by the digest
+        let mut interest =
+            Interest::new("/a".parse().unwrap()).with_application_parameters(&b"p"[..]);
+        interest.update_parameters_digest();
+        let mut wire = interest.encode().to_vec();
+        wire[1] += 2;
+        wire.extend_from_slice(&[200, 0]);
+        let decoded = Interest::decode(&mut Bytes::from(wire.clone())).unwrap();
+        assert_eq!(decoded.encode(), wire);
+        assert!(!decoded.verify_parameters_digest());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn signature_info_defaults() {
+        let mut interest = Interest::new("/a".parse().unwrap());
+        interest.sign(&DigestSha256).unwrap();
+        let info = interest.signature_info.unwrap();
+        assert_eq!(info.nonce.as_ref().unwrap().0.len(), 8);
+        assert!(info.time.is_some());
+        assert_eq!(info.seq_num, None);
+    }
+}
@@ -0,0 +1,840 @@
+use core::hash::{Hash, Hasher};
+use core::ops::Range;
+use core::time::Duration;
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::tlv::{decode_record, split_record, value_record};
+use crate::{
+    decode_ordered, encode_to_bytes, required_field, DecodeConfig, Name, NameComponent,
+    NonNegativeInteger, Result, SignatureInfo, SignatureValue, Signer, Tlv, TlvDecode, TlvEncode,
+    TlvError, UnknownTlvs, VarNum, Verifier,
+};
+
+/// The type of the content of a Data packet
+///
+/// Values without a constant are preserved as they are.
+#[derive(Debug, Clone, Copy)]
+pub struct ContentType(pub NonNegativeInteger);
+
+value_record!(ContentType, 24);
+
+impl ContentType {
+    /// Arbitrary content, the default if no content type is given
+    pub const BLOB: Self = Self(NonNegativeInteger::new(0));
+    /// A list of delegations
+    pub const LINK: Self = Self(NonNegativeInteger::new(1));
+    /// A public key
+    pub const KEY: Self = Self(NonNegativeInteger::new(2));
+    /// An application-level negative acknowledgement
+    pub const NACK: Self = Self(NonNegativeInteger::new(3));
+    /// A prefix announcement
+    pub const PREFIX_ANN: Self = Self(NonNegativeInteger::new(5));
+
+    /// Create a content type from its number
+    pub const fn new(value: u64) -> Self {
+        Self(NonNegativeInteger::new(value))
+    }
+
+    /// The number of the content type
+    pub const fn value(&self) -> u64 {
+        self.0.as_u64()
+    }
+}
+
+impl Default for ContentType {
+    fn default() -> Self {
+        Self::BLOB
+    }
+}
+
+/// Content types are equal if their numbers are, regardless of their encoding
+impl PartialEq for ContentType {
+    fn eq(&self, other: &Self) -> bool {
+        self.value() == other.value()
+    }
+}
+
+impl Eq for ContentType {}
+
+impl Hash for ContentType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value().hash(state);
+    }
+}
+
+/// How long the Data remains fresh after it was received, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FreshnessPeriod(pub NonNegativeInteger);
+
+value_record!(FreshnessPeriod, 25);
+
+impl From<Duration> for FreshnessPeriod {
+    fn from(period: Duration) -> Self {
+        Self(NonNegativeInteger::new(
+            period.as_millis().try_into().unwrap_or(u64::MAX),
+        ))
+    }
+}
+
+impl From<FreshnessPeriod> for Duration {
+    fn from(period: FreshnessPeriod) -> Self {
+        Duration::from_millis(period.0.as_u64())
+    }
+}
+
+/// The last name component of the last segment of a segmented object
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FinalBlockId(pub NameComponent);
+
+value_record!(FinalBlockId, 26);
+
+/// Properties of a Data packet that are not part of its name
+///
+/// When decoding, elements that are out of order or duplicated lead to an error, as do unknown
+/// critical elements. Unknown non-critical elements are skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MetaInfo {
+    /// The type of the content, [`ContentType::BLOB`] if not given
+    pub content_type: Option<ContentType>,
+    /// How long the Data remains fresh, zero if not given
+    pub freshness_period: Option<FreshnessPeriod>,
+    /// The last segment of the object the Data belongs to
+    pub final_block_id: Option<FinalBlockId>,
+}
+
+impl MetaInfo {
+    /// The TLV-TYPEs of the elements of a MetaInfo, in the order they must appear
+    const ELEMENTS: [usize; 3] = [ContentType::TYP, FreshnessPeriod::TYP, FinalBlockId::TYP];
+}
+
+impl Tlv for MetaInfo {
+    const TYP: usize = 20;
+
+    fn inner_size(&self) -> usize {
+        self.content_type.size() + self.freshness_period.size() + self.final_block_id.size()
+    }
+}
+
+impl TlvEncode for MetaInfo {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        VarNum::from(Self::TYP).encode_to(buf);
+        VarNum::from(self.inner_size()).encode_to(buf);
+        self.content_type.encode_to(buf);
+        self.freshness_period.encode_to(buf);
+        self.final_block_id.encode_to(buf);
+    }
+
+    fn size(&self) -> usize {
+        let inner_size = self.inner_size();
+        VarNum::from(Self::TYP).size() + VarNum::from(inner_size).size() + inner_size
+    }
+}
+
+impl TlvDecode for MetaInfo {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        decode_record(bytes, config, Some(Self::TYP), |_, mut inner| {
+            let config = config.nested()?;
+            let mut meta_info = MetaInfo::default();
+            decode_ordered(&mut inner, &config, &Self::ELEMENTS, None, |typ, record| {
+                match typ {
+                    ContentType::TYP => {
+                        meta_info.content_type = Some(TlvDecode::decode_with(record, &config)?)
+                    }
+                    FreshnessPeriod::TYP => {
+                        meta_info.freshness_period = Some(TlvDecode::decode_with(record, &config)?)
+                    }
+                    FinalBlockId::TYP => {
+                        meta_info.final_block_id = Some(TlvDecode::decode_with(record, &config)?)
+                    }
+                    _ => unreachable!("unknown elements are skipped"),
+                }
+                Ok(())
+            })?;
+            Ok(meta_info)
+        })
+        .map_err(|e| e.in_tlv(Some("MetaInfo"), Self::TYP))
+    }
+}
+
+/// The payload of a Data packet
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Content(pub Bytes);
+
+value_record!(Content, 21);
+
+/// The encoding a Data packet was decoded from
+#[derive(Debug)]
+struct DataWire {
+    wire: Bytes,
+    signed: Range<usize>,
+    /// The implicit digest of `wire`, computed on first use
+    #[cfg(feature = "crypto")]
+    digest: spin::Once<[u8; 32]>,
+}
+
+impl DataWire {
+    /// Locate the signed portion of `wire`, from the start of the Name to the end of the
+    /// SignatureInfo
+    fn new(wire: Bytes, config: &DecodeConfig) -> Result<Self> {
+        let (_, mut elements) = split_record(&mut wire.clone(), config)?;
+        let config = config.nested()?;
+        let len = wire.len();
+        let mut signed = 0..0;
+        while !elements.is_empty() {
+            let start = len - elements.len();
+            let (typ, _) = split_record(&mut elements, &config)?;
+            match usize::from(typ) {
+                Name::TYP => signed.start = start,
+                SignatureInfo::TYP => signed.end = len - elements.len(),
+                _ => {}
+            }
+        }
+        Ok(Self {
+            wire,
+            signed,
+            #[cfg(feature = "crypto")]
+            digest: spin::Once::new(),
+        })
+    }
+}
+
+impl Clone for DataWire {
+    fn clone(&self) -> Self {
+        Self {
+            wire: self.wire.clone(),
+            signed: self.signed.clone(),
+            #[cfg(feature = "crypto")]
+            digest: self
+                .digest
+                .get()
+                .map_or_else(spin::Once::new, |&digest| spin::Once::initialized(digest)),
+        }
+    }
+}
+
+/// A Data packet, as defined by the NDN packet format v0.3
+///
+/// A decoded Data packet keeps the bytes it was decoded from until it is modified. As long as it
+/// is, it encodes to exactly these bytes, including non-minimal encodings, so that its signature
+/// stays valid. Unknown non-critical elements are kept in [`extra`](Data::extra) and encoded where
+/// they appeared, so that they remain covered by the signature after re-signing. Equality and
+/// hashing consider all elements, but not the bytes they were decoded from.
+///
+/// When decoding, elements that are out of order or duplicated lead to an error, as do unknown
+/// critical elements and a missing SignatureInfo or SignatureValue.
+#[derive(Debug, Clone, Default)]
+pub struct Data {
+    name: Name,
+    meta_info: Option<MetaInfo>,
+    content: Option<Content>,
+    signature_info: Option<SignatureInfo>,
+    signature_value: Option<SignatureValue>,
+    extra: UnknownTlvs,
+    wire: Option<DataWire>,
+}
+
+impl Data {
+    /// The TLV-TYPEs of the elements of a Data packet, in the order they must appear
+    const ELEMENTS: [usize; 5] = [
+        Name::TYP,
+        MetaInfo::TYP,
+        Content::TYP,
+        SignatureInfo::TYP,
+        SignatureValue::TYP,
+    ];
+
+    /// Create an unsigned Data packet for `name` without content
+    pub fn new(name: Name) -> Self {
+        Self {
+            name,
+            ..Default::default()
+        }
+    }
+
+    /// Set the content
+    pub fn with_content(mut self, content: impl Into<Bytes>) -> Self {
+        self.set_content(Some(Content(content.into())));
+        self
+    }
+
+    /// Set the type of the content
+    pub fn with_content_type(mut self, content_type: ContentType) -> Self {
+        self.meta_info_mut().content_type = Some(content_type);
+        self
+    }
+
+    /// Set how long the Data remains fresh
+    pub fn with_freshness_period(mut self, period: Duration) -> Self {
+        self.meta_info_mut().freshness_period = Some(period.into());
+        self
+    }
+
+    /// Set the last segment of the object the Data belongs to
+    pub fn with_final_block_id(mut self, final_block_id: NameComponent) -> Self {
+        self.meta_info_mut().final_block_id = Some(FinalBlockId(final_block_id));
+        self
+    }
+
+    /// The name of the Data
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    /// The MetaInfo element, if present
+    pub fn meta_info(&self) -> Option<&MetaInfo> {
+        self.meta_info.as_ref()
+    }
+
+    /// The type of the content
+    ///
+    /// This is [`ContentType::BLOB`] if no content type is set.
+    pub fn content_type(&self) -> ContentType {
+        self.meta_info
+            .as_ref()
+            .and_then(|meta_info| meta_info.content_type)
+            .unwrap_or_default()
+    }
+
+    /// How long the Data remains fresh
+    ///
+    /// This is zero if no freshness period is set.
+    pub fn freshness_period(&self) -> Duration {
+        self.meta_info
+            .as_ref()
+            .and_then(|meta_info| meta_info.freshness_period)
+            .map_or(Duration::ZERO, Duration::from)
+    }
+
+    /// The last segment of the object the Data belongs to, if set
+    pub fn final_block_id(&self) -> Option<&NameComponent> {
+        let meta_info = self.meta_info.as_ref()?;
+        meta_info.final_block_id.as_ref().map(|id| &id.0)
+    }
+
+    /// The content, if present
+    pub fn content(&self) -> Option<&Bytes> {
+        self.content.as_ref().map(|content| &content.0)
+    }
+
+    /// The SignatureInfo element, if present
+    pub fn signature_info(&self) -> Option<&SignatureInfo> {
+        self.signature_info.as_ref()
+    }
+
+    /// The SignatureValue element, if present
+    pub fn signature_value(&self) -> Option<&SignatureValue> {
+        self.signature_value.as_ref()
+    }
+
+    /// The unknown non-critical elements
+    pub fn extra(&self) -> &UnknownTlvs {
+        &self.extra
+    }
+
+    /// Set the name of the Data
+    pub fn set_name(&mut self, name: Name) {
+        self.wire = None;
+        self.name = name;
+    }
+
+    /// Set or remove the MetaInfo element
+    pub fn set_meta_info(&mut self, meta_info: Option<MetaInfo>) {
+        self.wire = None;
+        self.meta_info = meta_info;
+    }
+
+    /// Set or remove the Content element
+    pub fn set_content(&mut self, content: Option<Content>) {
+        self.wire = None;
+        self.content = content;
+    }
+
+    /// Set or remove the SignatureInfo element
+    pub fn set_signature_info(&mut self, signature_info: Option<SignatureInfo>) {
+        self.wire = None;
+        self.signature_info = signature_info;
+    }
+
+    /// Set or remove the SignatureValue element
+    pub fn set_signature_value(&mut self, signature_value: Option<SignatureValue>) {
+        self.wire = None;
+        self.signature_value = signature_value;
+    }
+
+    /// Set the unknown non-critical elements
+    pub fn set_extra(&mut self, extra: UnknownTlvs) {
+        self.wire = None;
+        self.extra = extra;
+    }
+
+    fn meta_info_mut(&mut self) -> &mut MetaInfo {
+        self.wire = None;
+        self.meta_info.get_or_insert_with(MetaInfo::default)
+    }
+
+    /// The bytes the Data was decoded from, if it has not been modified since
+    pub fn wire(&self) -> Option<&Bytes> {
+        self.wire.as_ref().map(|wire| &wire.wire)
+    }
+
+    /// The implicit digest of the Data, the SHA-256 digest of its whole encoding
+    ///
+    /// For a decoded Data packet that has not been modified, this is the digest of the original
+    /// bytes, which is computed only once. Otherwise, the Data is encoded first.
+    #[cfg(feature = "crypto")]
+    pub fn implicit_digest(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        match &self.wire {
+            Some(wire) => *wire.digest.call_once(|| Sha256::digest(&wire.wire).into()),
+            None => Sha256::digest(self.encode()).into(),
+        }
+    }
+
+    /// The name of the Data followed by an ImplicitSha256DigestComponent with its
+    /// [`implicit_digest`](Data::implicit_digest)
+    ///
+    /// Unlike the name, the full name identifies exactly one packet.
+    #[cfg(feature = "crypto")]
+    pub fn full_name(&self) -> Name {
+        let mut name = self.name.clone();
+        name.push(NameComponent::implicit_sha256_digest(
+            self.implicit_digest(),
+        ));
+        name
+    }
+
+    /// The part of the Data covered by its signature, from the start of the Name to the end of the
+    /// SignatureInfo
+    ///
+    /// For a decoded Data packet that has not been modified, this is a slice of the original bytes,
+    /// including any unknown elements. Otherwise, the elements are encoded.
+    pub fn signed_portion(&self) -> Bytes {
+        if let Some(wire) = &self.wire {
+            return wire.wire.slice(wire.signed.clone());
+        }
+        let mut bytes = BytesMut::new();
+        self.name.encode_to(&mut bytes);
+        self.extra.encode_at(1, &mut bytes);
+        self.meta_info.encode_to(&mut bytes);
+        self.extra.encode_at(2, &mut bytes);
+        self.content.encode_to(&mut bytes);
+        self.extra.encode_at(3, &mut bytes);
+        self.signature_info.encode_to(&mut bytes);
+        bytes.freeze()
+    }
+
+    /// Sign the Data with `signer`
+    ///
+    /// This sets the signature type and key locator of the SignatureInfo from `signer`, keeping its
+    /// other elements if a SignatureInfo was present, and fills the SignatureValue with the
+    /// signature of [`signed_portion`](Data::signed_portion). If `signer` fails, the Data is left
+    /// unchanged.
+    pub fn sign(&mut self, signer: &(impl Signer + ?Sized)) -> Result<()> {
+        let mut info = self
+            .signature_info
+            .clone()
+            .unwrap_or_else(|| SignatureInfo::new(signer.signature_type()));
+        info.signature_type = signer.signature_type();
+        info.key_locator = signer.key_locator();
+        let mut signed = self.clone();
+        signed.set_signature_info(Some(info));
+        let signature = signer.sign(&signed.signed_portion())?;
+        signed.set_signature_value(Some(SignatureValue(signature)));
+        *self = signed;
+        Ok(())
+    }
+
+    /// Check the signature of the Data with `verifier`
+    ///
+    /// For a decoded Data packet, the signature is checked against the signed portion of the bytes
+    /// it was decoded from, not against a re-encoding. Returns `false` if the signature is missing
+    /// or its type does not match `verifier`.
+    pub fn verify(&self, verifier: &(impl Verifier + ?Sized)) -> bool {
+        let (Some(info), Some(value)) = (&self.signature_info, &self.signature_value) else {
+            return false;
+        };
+        info.signature_type == verifier.signature_type()
+            && verifier.verify(&self.signed_portion(), &value.0)
+    }
+
+    fn decode_element(
+        &mut self,
+        typ: usize,
+        record: &mut Bytes,
+        config: &DecodeConfig,
+    ) -> Result<()> {
+        let (_, value) = split_record(&mut record.clone(), config)?;
+        match typ {
+            Name::TYP => self.name = Name::decode_with(record, config)?,
+            MetaInfo::TYP => self.meta_info = Some(MetaInfo::decode_with(record, config)?),
+            Content::TYP => self.content = Some(Content(value)),
+            SignatureInfo::TYP => {
+                self.signature_info = Some(SignatureInfo::decode_with(record, config)?)
+            }
+            SignatureValue::TYP => self.signature_value = Some(SignatureValue(value)),
+            _ => unreachable!("unknown elements are kept in extra"),
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for Data {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.meta_info == other.meta_info
+            && self.content == other.content
+            && self.signature_info == other.signature_info
+            && self.signature_value == other.signature_value
+            && self.extra == other.extra
+    }
+}
+
+impl Eq for Data {}
+
+impl Hash for Data {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.meta_info.hash(state);
+        self.content.hash(state);
+        self.signature_info.hash(state);
+        self.signature_value.hash(state);
+        self.extra.hash(state);
+    }
+}
+
+impl Tlv for Data {
+    const TYP: usize = 6;
+
+    fn inner_size(&self) -> usize {
+        self.name.size()
+            + self.meta_info.size()
+            + self.content.size()
+            + self.signature_info.size()
+            + self.signature_value.size()
+            + self.extra.size()
+    }
+}
+
+impl TlvEncode for Data {
+    fn encode(&self) -> Bytes {
+        if let Some(wire) = &self.wire {
+            return wire.wire.clone();
+        }
+        let mut bytes = BytesMut::with_capacity(self.size());
+        self.encode_to(&mut bytes);
+        bytes.freeze()
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        if let Some(wire) = &self.wire {
+            buf.put_slice(&wire.wire);
+            return;
+        }
+        VarNum::from(Self::TYP).encode_to(buf);
+        VarNum::from(self.inner_size()).encode_to(buf);
+        self.extra.encode_at(0, buf);
+        self.name.encode_to(buf);
+        self.extra.encode_at(1, buf);
+        self.meta_info.encode_to(buf);
+        self.extra.encode_at(2, buf);
+        self.content.encode_to(buf);
+        self.extra.encode_at(3, buf);
+        self.signature_info.encode_to(buf);
+        self.extra.encode_at(4, buf);
+        self.signature_value.encode_to(buf);
+        self.extra.encode_at(5, buf);
+    }
+
+    fn size(&self) -> usize {
+        if let Some(wire) = &self.wire {
+            return wire.wire.len();
+        }
+        let inner_size = self.inner_size();
+        VarNum::from(Self::TYP).size() + VarNum::from(inner_size).size() + inner_size
+    }
+}
+
+impl TlvDecode for Data {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        let start_len = bytes.len();
+        let mut wire = bytes.clone();
+        let mut data = decode_record(bytes, config, Some(Self::TYP), |_, mut elements| {
+            let config = config.nested()?;
+            let mut data = Data::default();
+            let mut has_name = false;
+            let mut extra = UnknownTlvs::new();
+            decode_ordered(
+                &mut elements,
+                &config,
+                &Self::ELEMENTS,
+                Some(&mut extra),
+                |typ, record| {
+                    if !has_name && typ != Name::TYP {
+                        return Err(TlvError::MissingField { typ: Name::TYP });
+                    }
+                    has_name = true;
+                    data.decode_element(typ, record, &config)
+                },
+            )?;
+            data.extra = extra;
+            if !has_name {
+                return Err(TlvError::MissingField { typ: Name::TYP });
+            }
+            required_field(data.signature_info.as_ref(), SignatureInfo::TYP)?;
+            required_field(data.signature_value.as_ref(), SignatureValue::TYP)?;
+            Ok(data)
+        })
+        .map_err(|e| e.in_tlv(Some("Data"), Self::TYP))?;
+        wire.truncate(start_len - bytes.len());
+        data.wire = Some(DataWire::new(wire, config)?);
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::tests::Failing;
+    use crate::SignatureType;
+
+    #[rustfmt::skip]
+    const WIRE: &[u8] = &[
+        6, 34,
+            7, 3, 8, 1, b'a',
+            20, 12,
+                24, 1, 0,
+                25, 2, 0x03, 0xE8,
+                26, 3, 50, 1, 5,
+            21, 2, b'h', b'i',
+            22, 3, 27, 1, 0,
+            23, 4, 1, 2, 3, 4,
+    ];
+
+    fn data() -> Data {
+        let mut data = Data::new("/a".parse().unwrap())
+            .with_content_type(ContentType::BLOB)
+            .with_freshness_period(Duration::from_secs(1))
+            .with_final_block_id(NameComponent::segment(5))
+            .with_content(&b"hi"[..]);
+        data.set_signature_info(Some(SignatureInfo::new(SignatureType::DigestSha256)));
+        data.set_signature_value(Some(SignatureValue(Bytes::from_static(&[1, 2, 3, 4]))));
+        data
+    }
+
+    #[test]
+    fn encode_decode() {
+        let data = data();
+        assert_eq!(data.encode(), WIRE);
+        assert_eq!(data.size(), WIRE.len());
+        assert_eq!(data.signed_portion(), &WIRE[2..30]);
+        assert_eq!(data.wire(), None);
+
+        let decoded = Data::decode(&mut Bytes::from_static(WIRE)).unwrap();
+        assert_eq!(decoded, data);
+        assert_eq!(decoded.wire().unwrap(), WIRE);
+        assert_eq!(decoded.signed_portion(), &WIRE[2..30]);
+        assert_eq!(decoded.content_type(), ContentType::BLOB);
+        assert_eq!(decoded.freshness_period(), Duration::from_secs(1));
+        assert_eq!(decoded.final_block_id(), Some(&NameComponent::segment(5)));
+        assert_eq!(decoded.content().unwrap(), &b"hi"[..]);
+    }
+
+    #[test]
+    fn minimal() {
+        let wire = [6, 12, 7, 3, 8, 1, b'a', 22, 3, 27, 1, 0, 23, 0];
+        let data = Data::decode(&mut Bytes::copy_from_slice(&wire)).unwrap();
+        assert_eq!(data.meta_info(), None);
+        assert_eq!(data.content(), None);
+        assert_eq!(data.content_type(), ContentType::BLOB);
+        assert_eq!(data.freshness_period(), Duration::ZERO);
+        assert_eq!(data.signed_portion(), &wire[2..12]);
+        assert_eq!(
+            Data::new("/a".parse().unwrap()).signed_portion(),
+            &wire[2..7]
+        );
+    }
+
+    #[test]
+    fn content_type() {
+        let constants = [
+            (ContentType::BLOB, 0),
+            (ContentType::LINK, 1),
+            (ContentType::KEY, 2),
+            (ContentType::NACK, 3),
+            (ContentType::PREFIX_ANN, 5),
+        ];
+        for (content_type, value) in constants {
+            assert_eq!(content_type.value(), value);
+        }
+        assert_eq!(ContentType(NonNegativeInteger::U16(2)), ContentType::KEY);
+
+        let mut data = data();
+        data.set_meta_info(Some(MetaInfo {
+            content_type: Some(ContentType::new(1000)),
+            ..Default::default()
+        }));
+        let decoded = Data::decode(&mut data.encode()).unwrap();
+        assert_eq!(decoded.content_type(), ContentType::new(1000));
+        assert_eq!(decoded.encode(), data.encode());
+    }
+
+    #[test]
+    fn preserves_wire() {
+        // An unknown non-critical element and a non-minimal ContentType
+        #[rustfmt::skip]
+        let wire: &[u8] = &[
+            6, 21,
+                7, 3, 8, 1, b'a',
+                20, 4, 24, 2, 0, 2,
+                200, 1, 0,
+                22, 3, 27, 1, 0,
+                23, 0,
+        ];
+        let mut data = Data::decode(&mut Bytes::from_static(wire)).unwrap();
+        assert_eq!(data.content_type(), ContentType::KEY);
+        assert_eq!(data.encode(), wire);
+        assert_eq!(data.size(), wire.len());
+        assert_eq!(data.signed_portion(), &wire[2..21]);
+
+        data.set_signature_value(Some(SignatureValue(Bytes::from_static(&[9]))));
+        assert_eq!(data.wire(), None);
+        assert_eq!(
+            data.signed_portion(),
+            &[7, 3, 8, 1, b'a', 20, 4, 24, 2, 0, 2, 200, 1, 0, 22, 3, 27, 1, 0][..]
+        );
+        assert_eq!(
+            Data::decode(&mut data.encode())
+                .unwrap()
+                .signature_value()
+                .unwrap()
+                .0,
+            &[9][..]
+        );
+    }
+
+    #[test]
+    fn unknown_elements() {
+        let wire = [6, 15, 7, 3, 8, 1, b'a', 200, 1, 9, 22, 3, 27, 1, 0, 23, 0];
+        let mut data = Data::decode(&mut Bytes::copy_from_slice(&wire)).unwrap();
+        assert_eq!(data.extra().len(), 1);
+        let mut without = data.clone();
+        without.set_extra(UnknownTlvs::new());
+        assert_ne!(without, data);
+        assert_eq!(
+            without.encode(),
+            &[6, 12, 7, 3, 8, 1, b'a', 22, 3, 27, 1, 0, 23, 0][..]
+        );
+
+        // Re-signing keeps the element and covers it
+        data.set_signature_value(Some(SignatureValue(Bytes::from_static(&[1]))));
+        assert_eq!(
+            data.encode(),
+            &[6, 16, 7, 3, 8, 1, b'a', 200, 1, 9, 22, 3, 27, 1, 0, 23, 1, 1][..]
+        );
+        assert_eq!(data.size(), 18);
+        assert_eq!(
+            data.signed_portion(),
+            &[7, 3, 8, 1, b'a', 200, 1, 9, 22, 3, 27, 1, 0][..]
+        );
+        #[cfg(feature = "crypto")]
+        {
+            data.sign(&crate::DigestSha256).unwrap();
+            let decoded = Data::decode(&mut data.encode()).unwrap();
+            assert_eq!(decoded.extra(), data.extra());
+            assert!(decoded.verify(&crate::DigestSha256));
+        }
+    }
+
+    #[test]
+    fn failed_signing() {
+        let mut data = Data::decode(&mut Bytes::from_static(WIRE)).unwrap();
+        assert_eq!(data.sign(&Failing), Err(TlvError::SigningFailed));
+        assert_eq!(data.wire(), Some(&Bytes::from_static(WIRE)));
+        assert_eq!(
+            data.signature_info().unwrap().signature_type,
+            SignatureType::DigestSha256
+        );
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn implicit_digest() {
+        // Computed independently with Python's hashlib
+        const DIGEST: [u8; 32] = [
+            0x93, 0x66, 0xAB, 0x0A, 0x68, 0x95, 0x3D, 0xE2, 0x3E, 0xF5, 0x38, 0x4F, 0xD9, 0x61,
+            0x26, 0x41, 0x08, 0xDB, 0x2D, 0xF3, 0x4C, 0xAE, 0x20, 0x77, 0xD2, 0x9B, 0x15, 0x43,
+            0x05, 0x91, 0x29, 0x6A,
+        ];
+        let decoded = Data::decode(&mut Bytes::from_static(WIRE)).unwrap();
+        assert_eq!(decoded.implicit_digest(), DIGEST);
+        assert_eq!(decoded.implicit_digest(), DIGEST);
+        assert_eq!(data().implicit_digest(), DIGEST);
+
+        let full_name = decoded.full_name();
+        assert_eq!(full_name.len(), 2);
+        assert_eq!(
+            full_name.get(1).unwrap().as_implicit_sha256_digest(),
+            Some(&DIGEST)
+        );
+
+        let mut modified = decoded.clone();
+        modified.set_content(Some(Content(Bytes::from_static(b"ho"))));
+        assert_ne!(modified.implicit_digest(), DIGEST);
+        assert_eq!(decoded.implicit_digest(), DIGEST);
+    }
+
+    #[test]
+    fn errors() {
+        let cases: [(&[u8], TlvError); 5] = [
+            (
+                &[6, 7, 7, 3, 8, 1, b'a', 23, 0],
+                TlvError::MissingField { typ: 22 },
+            ),
+            (
+                &[6, 10, 7, 3, 8, 1, b'a', 22, 3, 27, 1, 0],
+                TlvError::MissingField { typ: 23 },
+            ),
+            (
+                &[6, 13, 7, 3, 8, 1, b'a', 21, 0, 20, 0, 22, 0, 23, 0],
+                TlvError::OutOfOrder { typ: 20 },
+            ),
+            (&[6, 4, 21, 0, 7, 0], TlvError::MissingField { typ: 7 }),
+            (
+                &[5, 0],
+                TlvError::TypeMismatch {
+                    expected: 6,
+                    found: 5,
+                },
+            ),
+        ];
+        for (wire, error) in cases {
+            assert_eq!(
+                Data::decode(&mut Bytes::copy_from_slice(wire)).unwrap_err(),
+                error
+            );
+        }
+
+        let wire = [6, 9, 7, 0, 22, 5, 27, 1, 0, 201, 0];
+        let error = Data::decode(&mut Bytes::copy_from_slice(&wire)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown critical TLV record of type 201 at byte 9 in Data(6) > SignatureInfo(22)"
+        );
+    }
+}
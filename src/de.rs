@@ -0,0 +1,744 @@
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+
+use bytes::{Buf, Bytes};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+
+use crate::ser::type_number;
+use crate::{
+    tlv_typ_critical, DecodeConfig, GenericTlv, NonNegativeInteger, Result, TlvDecode, TlvError,
+};
+
+/// Deserialize a value from data in TLV format
+///
+/// This is the counterpart to [`to_bytes`](crate::to_bytes), see there for how values are mapped.
+/// Records of unknown types within a struct are skipped if they are non-critical and lead to
+/// [`TlvError::UnknownCritical`] otherwise. A field without records takes its `#[serde(default)]`
+/// if it has one, an `Option` is `None` and a sequence is empty, anything else leads to
+/// [`TlvError::MissingField`].
+///
+/// The TLV format is not self-describing, so types that rely on `deserialize_any`, such as
+/// untagged enums, are not supported.
+pub fn from_bytes<T: DeserializeOwned>(bytes: Bytes) -> Result<T> {
+    from_bytes_with(bytes, &DecodeConfig::default())
+}
+
+/// Deserialize a value from data in TLV format, using the given configuration
+///
+/// See [`from_bytes`] for details.
+pub fn from_bytes_with<T: DeserializeOwned>(bytes: Bytes, config: &DecodeConfig) -> Result<T> {
+    config.check_total_length(bytes.len())?;
+    let empty = Rc::new(EmptyFields::default());
+    loop {
+        empty.structs.set(0);
+        let result = T::deserialize(ValueDeserializer {
+            bytes: bytes.clone(),
+            config: *config,
+            empty: empty.clone(),
+        });
+        if result.is_ok() || !empty.retry.take() {
+            return result;
+        }
+    }
+}
+
+/// The struct fields without records that are deserialized as present
+///
+/// Fields without records are left out of a struct, so serde handles them like any missing field
+/// and applies `#[serde(default)]`. Serde reports a sequence without `#[serde(default)]` as
+/// missing though, so when a left out field is reported missing, the value is deserialized again
+/// with that field present. A sequence then becomes empty, anything else is missing either way.
+#[derive(Default)]
+struct EmptyFields {
+    /// The number of structs started so far, which identifies a struct across attempts
+    structs: Cell<usize>,
+    /// The struct and TLV-TYPE of each field that is present without records
+    present: RefCell<Vec<(usize, usize)>>,
+    /// Whether a field was added to `present` since the last attempt
+    retry: Cell<bool>,
+}
+
+/// Split `bytes` into the type and value of each record
+fn records(mut bytes: Bytes, config: &DecodeConfig) -> Result<Vec<(usize, Bytes)>> {
+    let mut records = Vec::new();
+    while bytes.has_remaining() {
+        config.check_elements(records.len() + 1)?;
+        let tlv = GenericTlv::<Bytes>::decode_with(&mut bytes, config)?;
+        records.push((tlv.typ.into(), tlv.content));
+    }
+    Ok(records)
+}
+
+/// Deserializes a value from the TLV-VALUE of a record
+struct ValueDeserializer {
+    bytes: Bytes,
+    config: DecodeConfig,
+    empty: Rc<EmptyFields>,
+}
+
+impl ValueDeserializer {
+    fn integer(&self) -> Result<u64> {
+        let mut bytes = self.bytes.clone();
+        Ok(NonNegativeInteger::decode_with(&mut bytes, &self.config)?.as_u64())
+    }
+
+    fn integer_as<T: TryFrom<u64>>(&self) -> Result<T> {
+        T::try_from(self.integer()?).map_err(|_| TlvError::custom("integer out of range"))
+    }
+
+    fn fixed<const N: usize>(&self) -> Result<[u8; N]> {
+        self.bytes[..]
+            .try_into()
+            .map_err(|_| TlvError::UnexpectedLength)
+    }
+
+    fn string(self) -> Result<String> {
+        String::from_utf8(self.bytes.to_vec()).map_err(|_| TlvError::FormatError)
+    }
+
+    fn expect_empty(&self) -> Result<()> {
+        if !self.bytes.is_empty() {
+            return Err(TlvError::UnexpectedLength);
+        }
+        Ok(())
+    }
+
+    fn nested(&self) -> Result<DecodeConfig> {
+        self.config.nested()
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = TlvError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+        Err(TlvError::custom(
+            "TLV is not self-describing, the type must be known to deserialize it",
+        ))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.integer()? {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            _ => Err(TlvError::FormatError),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i8(self.integer_as()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i16(self.integer_as()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i32(self.integer_as()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i64(self.integer_as()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u8(self.integer_as()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u16(self.integer_as()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u32(self.integer_as()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u64(self.integer()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_f32(f32::from_be_bytes(self.fixed()?))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_f64(f64::from_be_bytes(self.fixed()?))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let string = self.string()?;
+        let mut chars = string.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(TlvError::FormatError),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_string(self.string()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_byte_buf(self.bytes.to_vec())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_byte_buf(self.bytes.to_vec())
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.bytes.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.expect_empty()?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+        Err(TlvError::custom(
+            "sequences can only be deserialized as struct fields",
+        ))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value> {
+        Err(TlvError::custom("tuples cannot be deserialized from TLV"))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value> {
+        Err(TlvError::custom(
+            "tuple structs cannot be deserialized from TLV",
+        ))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let config = self.nested()?;
+        visitor.visit_map(MapAccess {
+            records: records(self.bytes, &config)?.into_iter(),
+            value: None,
+            config,
+            empty: self.empty,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let config = self.nested()?;
+        let types = fields
+            .iter()
+            .map(|field| type_number(field))
+            .collect::<Result<Vec<_>>>()?;
+        let mut grouped: Vec<Vec<Bytes>> = types.iter().map(|_| Vec::new()).collect();
+        for (typ, value) in records(self.bytes, &config)? {
+            match types.iter().position(|&t| t == typ) {
+                Some(i) => grouped[i].push(value),
+                None if tlv_typ_critical(typ) => return Err(TlvError::UnknownCritical { typ }),
+                None => {}
+            }
+        }
+        let id = self.empty.structs.get();
+        self.empty.structs.set(id + 1);
+        let mut left_out = Vec::new();
+        let present = fields
+            .iter()
+            .zip(types)
+            .zip(grouped)
+            .filter(|((_, typ), values)| {
+                let present =
+                    !values.is_empty() || self.empty.present.borrow().contains(&(id, *typ));
+                if !present {
+                    left_out.push(*typ);
+                }
+                present
+            })
+            .collect::<Vec<_>>();
+        let empty = self.empty.clone();
+        visitor
+            .visit_map(StructAccess {
+                fields: present.into_iter(),
+                value: None,
+                config,
+                empty: self.empty,
+            })
+            .inspect_err(|e| {
+                if let TlvError::MissingField { typ } = *e.kind() {
+                    if left_out.contains(&typ) {
+                        empty.present.borrow_mut().push((id, typ));
+                        empty.retry.set(true);
+                    }
+                }
+            })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let config = self.nested()?;
+        let mut records = records(self.bytes, &config)?;
+        if records.len() != 1 {
+            return Err(TlvError::UnexpectedLength);
+        }
+        let (typ, value) = records.remove(0);
+        let variant = variants
+            .iter()
+            .find(|variant| type_number(variant).is_ok_and(|t| t == typ))
+            .ok_or(TlvError::UnknownCritical { typ })?;
+        visitor.visit_enum(EnumAccess {
+            variant,
+            value: ValueDeserializer {
+                bytes: value,
+                config,
+                empty: self.empty,
+            },
+        })
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value> {
+        Err(TlvError::custom(
+            "identifiers cannot be deserialized from TLV",
+        ))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+/// Deserializes a struct field from all records of type `typ`
+struct FieldDeserializer {
+    typ: usize,
+    values: Vec<Bytes>,
+    config: DecodeConfig,
+    empty: Rc<EmptyFields>,
+}
+
+impl FieldDeserializer {
+    fn single(mut self) -> Result<ValueDeserializer> {
+        match self.values.len() {
+            0 => Err(TlvError::MissingField { typ: self.typ }),
+            1 => Ok(ValueDeserializer {
+                bytes: self.values.remove(0),
+                config: self.config,
+                empty: self.empty,
+            }),
+            _ => Err(TlvError::DuplicateField { typ: self.typ }),
+        }
+    }
+}
+
+macro_rules! forward_to_single {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value> {
+                self.single()?.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for FieldDeserializer {
+    type Error = TlvError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.values.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let config = self.config;
+        let empty = self.empty;
+        visitor.visit_seq(de::value::SeqDeserializer::new(
+            self.values.into_iter().map(|bytes| ValueDeserializer {
+                bytes,
+                config,
+                empty: empty.clone(),
+            }),
+        ))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_single! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+}
+
+impl<'de> IntoDeserializer<'de, TlvError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Provides the fields of a struct along with the records of their type
+struct StructAccess {
+    fields: alloc::vec::IntoIter<((&'static &'static str, usize), Vec<Bytes>)>,
+    value: Option<FieldDeserializer>,
+    config: DecodeConfig,
+    empty: Rc<EmptyFields>,
+}
+
+impl<'de> de::MapAccess<'de> for StructAccess {
+    type Error = TlvError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        let Some(((&field, typ), values)) = self.fields.next() else {
+            return Ok(None);
+        };
+        self.value = Some(FieldDeserializer {
+            typ,
+            values,
+            config: self.config,
+            empty: self.empty.clone(),
+        });
+        seed.deserialize(de::value::StrDeserializer::<TlvError>::new(field))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| TlvError::custom("struct value without field"))?;
+        seed.deserialize(value)
+    }
+}
+
+/// Provides the records of a map, using their TLV-TYPE as key
+struct MapAccess {
+    records: alloc::vec::IntoIter<(usize, Bytes)>,
+    value: Option<Bytes>,
+    config: DecodeConfig,
+    empty: Rc<EmptyFields>,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess {
+    type Error = TlvError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        let Some((typ, value)) = self.records.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(de::value::U64Deserializer::<TlvError>::new(typ as u64))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let bytes = self
+            .value
+            .take()
+            .ok_or_else(|| TlvError::custom("map value without key"))?;
+        seed.deserialize(ValueDeserializer {
+            bytes,
+            config: self.config,
+            empty: self.empty.clone(),
+        })
+    }
+}
+
+/// Provides the variant of an enum, selected by the TLV-TYPE of its record
+struct EnumAccess {
+    variant: &'static str,
+    value: ValueDeserializer,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = TlvError;
+    type Variant = ValueDeserializer;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant)> {
+        let variant =
+            seed.deserialize(de::value::StrDeserializer::<TlvError>::new(self.variant))?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for ValueDeserializer {
+    type Error = TlvError;
+
+    fn unit_variant(self) -> Result<()> {
+        self.expect_empty()
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value> {
+        Err(TlvError::custom(
+            "tuple variants cannot be deserialized from TLV",
+        ))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+    use alloc::vec;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::{to_bytes, DecodeLimits};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(rename = "7")]
+        name: Vec<String>,
+        count_200: u32,
+        flag_202: Option<bool>,
+        inner_204: Inner,
+        ratio_210: f64,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Inner {
+        #[serde(rename = "206")]
+        value: u64,
+        kind_208: Kind,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Kind {
+        #[serde(rename = "1")]
+        Empty,
+        #[serde(rename = "3")]
+        Text(String),
+        #[serde(rename = "5")]
+        Pair {
+            #[serde(rename = "6")]
+            a: u8,
+            #[serde(rename = "8")]
+            b: char,
+        },
+    }
+
+    fn record(kind: Kind) -> Record {
+        Record {
+            name: vec!["a".into(), "bc".into()],
+            count_200: 0x100,
+            flag_202: Some(true),
+            inner_204: Inner {
+                value: 1,
+                kind_208: kind,
+            },
+            ratio_210: 0.5,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for kind in [
+            Kind::Empty,
+            Kind::Text("x".into()),
+            Kind::Pair { a: 1, b: 'ü' },
+        ] {
+            let record = record(kind);
+            let bytes = to_bytes(&record).unwrap();
+            assert_eq!(from_bytes::<Record>(bytes).unwrap(), record);
+        }
+    }
+
+    #[test]
+    fn missing_and_empty() {
+        // Fields may appear in any order, and absent sequences and options are empty
+        let bytes = Bytes::from_static(&[204, 6, 208, 2, 1, 0, 206, 0, 200, 1, 5, 210, 0]);
+        assert_eq!(
+            from_bytes::<Record>(bytes).unwrap_err(),
+            TlvError::UnexpectedLength
+        );
+        let bytes = Bytes::from_static(&[
+            204, 7, 208, 2, 1, 0, 206, 1, 0, 200, 1, 5, 210, 8, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]);
+        let record = from_bytes::<Record>(bytes).unwrap();
+        assert!(record.name.is_empty());
+        assert_eq!(record.flag_202, None);
+        assert_eq!(record.count_200, 5);
+
+        let bytes = Bytes::from_static(&[204, 7, 208, 2, 1, 0, 206, 1, 0]);
+        assert_eq!(
+            from_bytes::<Record>(bytes).unwrap_err(),
+            TlvError::MissingField { typ: 200 }
+        );
+    }
+
+    #[test]
+    fn defaults() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Outer {
+            #[serde(default)]
+            a_200: u32,
+            b_202: u8,
+            c_204: Vec<u8>,
+            inner_206: Nested,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Nested {
+            d_208: Vec<u8>,
+            #[serde(default = "nine")]
+            e_210: u8,
+        }
+
+        fn nine() -> u8 {
+            9
+        }
+
+        let bytes = Bytes::from_static(&[202, 1, 5, 206, 0]);
+        assert_eq!(
+            from_bytes::<Outer>(bytes).unwrap(),
+            Outer {
+                a_200: 0,
+                b_202: 5,
+                c_204: vec![],
+                inner_206: Nested {
+                    d_208: vec![],
+                    e_210: 9,
+                },
+            }
+        );
+        let bytes = Bytes::from_static(&[200, 1, 1, 206, 0]);
+        assert_eq!(
+            from_bytes::<Outer>(bytes).unwrap_err(),
+            TlvError::MissingField { typ: 202 }
+        );
+    }
+
+    #[test]
+    fn duplicate_and_unknown() {
+        assert_eq!(
+            from_bytes::<Inner>(Bytes::from_static(&[206, 1, 0, 206, 1, 1])).unwrap_err(),
+            TlvError::DuplicateField { typ: 206 }
+        );
+        let inner =
+            from_bytes::<Inner>(Bytes::from_static(&[220, 0, 206, 1, 9, 208, 2, 1, 0])).unwrap();
+        assert_eq!(inner.value, 9);
+        assert_eq!(
+            from_bytes::<Inner>(Bytes::from_static(&[221, 0, 206, 1, 9, 208, 2, 1, 0]))
+                .unwrap_err(),
+            TlvError::UnknownCritical { typ: 221 }
+        );
+    }
+
+    #[test]
+    fn integers() {
+        #[derive(Debug, Deserialize)]
+        struct Small {
+            #[serde(rename = "8")]
+            _value: u8,
+        }
+        assert_eq!(
+            from_bytes::<BTreeMap<u64, u64>>(Bytes::from_static(&[8, 2, 1, 0])).unwrap(),
+            [(8, 256)].into_iter().collect()
+        );
+        assert!(from_bytes::<Small>(Bytes::from_static(&[8, 2, 1, 0])).is_err());
+        assert_eq!(
+            from_bytes::<Small>(Bytes::from_static(&[8, 3, 1, 0, 0])).unwrap_err(),
+            TlvError::UnexpectedLength
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let config = DecodeConfig::new().with_limits(DecodeLimits::new().with_max_depth(1));
+        let bytes = to_bytes(&record(Kind::Empty)).unwrap();
+        assert_eq!(
+            from_bytes_with::<Record>(bytes, &config).unwrap_err(),
+            TlvError::DepthLimitExceeded { max: 1 }
+        );
+    }
+}
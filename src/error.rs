@@ -1,3 +1,7 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
 use thiserror::Error;
 
 /// Common error enum for library functions
@@ -19,19 +23,295 @@ pub enum TlvError {
     #[error("TLV Record had unexpected length")]
     UnexpectedLength,
 
+    /// A number was not encoded using the smallest possible representation
+    #[error("Number does not use minimal encoding")]
+    NonMinimalEncoding,
+
     /// Data being read was not in the expected format
     #[error("Data had unexpected format")]
     FormatError,
 
+    /// A TLV record was larger than the permitted maximum
+    #[error("TLV record exceeds maximum size of {max} bytes")]
+    RecordTooLarge {
+        /// The maximum permitted size of a record in bytes
+        max: usize,
+    },
+
+    /// TLV records were nested deeper than permitted by [`DecodeLimits`](crate::DecodeLimits)
+    #[error("TLV records nested deeper than {max} levels")]
+    DepthLimitExceeded {
+        /// The maximum permitted depth
+        max: usize,
+    },
+
+    /// The data to decode was larger than permitted by [`DecodeLimits`](crate::DecodeLimits)
+    #[error("Data exceeds maximum length of {max} bytes")]
+    LengthLimitExceeded {
+        /// The maximum permitted length in bytes
+        max: usize,
+    },
+
+    /// A collection had more elements than permitted by [`DecodeLimits`](crate::DecodeLimits)
+    #[error("Collection exceeds maximum of {max} elements")]
+    ElementLimitExceeded {
+        /// The maximum permitted number of elements
+        max: usize,
+    },
+
+    /// A TLV record declared a length larger than permitted by [`DecodeLimits`](crate::DecodeLimits)
+    #[error("TLV record declares length exceeding maximum of {max} bytes")]
+    TlvLengthLimitExceeded {
+        /// The maximum permitted TLV-LENGTH
+        max: usize,
+    },
+
+    /// A TLV record that may appear at most once was found multiple times
+    #[error("Duplicate TLV record of type {typ}")]
+    DuplicateField {
+        /// The TLV-TYPE of the duplicated record
+        typ: usize,
+    },
+
+    /// A required TLV record was not found
+    #[error("Missing required TLV record of type {typ}")]
+    MissingField {
+        /// The TLV-TYPE of the missing record
+        typ: usize,
+    },
+
+    /// A critical TLV record of an unknown type was found
+    #[error("Unknown critical TLV record of type {typ}")]
+    UnknownCritical {
+        /// The TLV-TYPE of the unknown record
+        typ: usize,
+    },
+
+    /// A TLV record appeared after records that must follow it
+    #[error("TLV record of type {typ} is out of order")]
+    OutOfOrder {
+        /// The TLV-TYPE of the misplaced record
+        typ: usize,
+    },
+
+    /// A string was not a valid NDN URI
+    #[error("Invalid NDN URI: {reason}")]
+    InvalidUri {
+        /// What was wrong with the URI
+        reason: &'static str,
+    },
+
+    /// Key material could not be parsed
+    #[error("Invalid key: {reason}")]
+    InvalidKey {
+        /// What was wrong with the key
+        reason: &'static str,
+    },
+
+    /// A signature could not be created
+    #[error("Failed to create signature")]
+    SigningFailed,
+
+    /// Another error, annotated with where in the data it occurred
+    ///
+    /// Use [`TlvError::kind`] to get the underlying error.
+    #[error("{source}{context}")]
+    WithContext {
+        /// The underlying error
+        source: Box<TlvError>,
+        /// Where in the data the error occurred
+        context: ErrorContext,
+    },
+
+    /// An error reported by a serde implementation
+    #[cfg(feature = "serde")]
+    #[error("{0}")]
+    Custom(alloc::string::String),
+
     /// An error during an IO operation
+    #[cfg(feature = "std")]
     #[error("IO Error")]
-    IOError(std::io::Error),
+    IOError(#[from] std::io::Error),
+}
+
+/// Location of an error within the data being decoded
+///
+/// Decoders record positions relative to their own input using [`TlvError::at`],
+/// [`TlvError::within`] and [`TlvError::relative_to`], so they stay correct when data is copied.
+/// Decoders generated by the `Tlv` derive macro record no context. Errors from within their fields
+/// are reported at the start of the derived record, and the record is missing from the path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Offset in bytes from the start of the data passed to the outermost decoder
+    pub offset: Option<usize>,
+    /// The TLV records and fields containing the error, outermost first
+    pub path: Vec<PathSegment>,
+    /// Length of the data that `offset` is relative to, once it has been declared
+    base: Option<usize>,
+}
+
+/// One element of the path to an error, see [`ErrorContext`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A TLV record
+    Tlv {
+        /// Name of the type representing the record, if known
+        name: Option<&'static str>,
+        /// The TLV-TYPE of the record
+        typ: usize,
+    },
+    /// A field of a struct
+    Field(&'static str),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PathSegment::Tlv {
+                name: Some(name),
+                typ,
+            } => write!(f, "{name}({typ})"),
+            PathSegment::Tlv { name: None, typ } => write!(f, "TLV({typ})"),
+            PathSegment::Field(name) => f.write_str(name),
+        }
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(offset) = self.offset {
+            write!(f, " at byte {offset}")?;
+        }
+        for (i, segment) in self.path.iter().enumerate() {
+            f.write_str(if i == 0 { " in " } else { " > " })?;
+            segment.fmt(f)?;
+        }
+        Ok(())
+    }
 }
 
+impl TlvError {
+    /// The underlying error, without any [`ErrorContext`]
+    ///
+    /// Match on this instead of the error itself to handle errors regardless of their context.
+    pub fn kind(&self) -> &TlvError {
+        match self {
+            TlvError::WithContext { source, .. } => source.kind(),
+            _ => self,
+        }
+    }
+
+    /// Remove any [`ErrorContext`], returning the underlying error
+    pub fn into_kind(self) -> TlvError {
+        match self {
+            TlvError::WithContext { source, .. } => source.into_kind(),
+            _ => self,
+        }
+    }
+
+    /// Where in the data the error occurred, if known
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            TlvError::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    fn context_mut(&mut self) -> &mut ErrorContext {
+        if !matches!(self, TlvError::WithContext { .. }) {
+            let source = core::mem::replace(self, TlvError::FormatError);
+            *self = TlvError::WithContext {
+                source: Box::new(source),
+                context: ErrorContext::default(),
+            };
+        }
+        match self {
+            TlvError::WithContext { context, .. } => context,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Record that the error occurred `offset` bytes into the data being decoded
+    ///
+    /// Has no effect if the position of the error is already known.
+    pub fn at(mut self, offset: usize) -> Self {
+        self.context_mut().offset.get_or_insert(offset);
+        self
+    }
+
+    /// Record that the error occurred while decoding the `len` bytes that start `start` bytes into
+    /// the data being decoded
+    ///
+    /// If the decoder of those bytes declared the position of the error using
+    /// [`relative_to`](TlvError::relative_to), or it was recorded using [`at`](TlvError::at) for
+    /// those bytes, the position is moved by `start`. A position declared relative to data of a
+    /// different length was reported by a decoder nested in one that does not record positions,
+    /// such as those generated by the `Tlv` derive macro, and is replaced by `start`. Errors with
+    /// no known position are assumed to have occurred at `start`.
+    pub fn within(mut self, start: usize, len: usize) -> Self {
+        let context = self.context_mut();
+        context.offset = match (context.offset, context.base.take()) {
+            (Some(offset), None) => Some(start + offset),
+            (Some(offset), Some(base)) if base == len => Some(start + offset),
+            _ => Some(start),
+        };
+        self
+    }
+
+    /// Declare that the position of the error is relative to the `len` bytes of data being decoded
+    ///
+    /// Decoders call this on the errors they return, so that callers can use
+    /// [`within`](TlvError::within). Errors with no known position are assumed to have occurred at
+    /// the start of the data.
+    pub fn relative_to(mut self, len: usize) -> Self {
+        let context = self.context_mut();
+        if context.base.is_some_and(|base| base != len) {
+            context.offset = None;
+        }
+        context.offset.get_or_insert(0);
+        context.base = Some(len);
+        self
+    }
+
+    /// Record that the error occurred within a TLV record
+    ///
+    /// `name` is the name of the type representing the record, if any. The record is added to the
+    /// start of the path, so this should be called while unwinding from the innermost record.
+    pub fn in_tlv(mut self, name: Option<&'static str>, typ: usize) -> Self {
+        self.context_mut()
+            .path
+            .insert(0, PathSegment::Tlv { name, typ });
+        self
+    }
+
+    /// Record that the error occurred within a record of type `typ`, named as in [`ndn_type`]
+    ///
+    /// Has no effect if the path already starts with that record, because its decoder recorded it.
+    ///
+    /// [`ndn_type`]: crate::ndn_type
+    pub(crate) fn in_record(self, typ: usize) -> Self {
+        match self.context().and_then(|context| context.path.first()) {
+            Some(&PathSegment::Tlv { typ: outer, .. }) if outer == typ => self,
+            _ => self.in_tlv(crate::ndn_type(typ).map(|t| t.name), typ),
+        }
+    }
+
+    /// Record that the error occurred within the field `name` of a struct
+    ///
+    /// The field is added to the start of the path, so this should be called while unwinding from
+    /// the innermost record.
+    pub fn in_field(mut self, name: &'static str) -> Self {
+        self.context_mut().path.insert(0, PathSegment::Field(name));
+        self
+    }
+}
+
+/// Errors compare equal if their [`kind`](TlvError::kind) is equal, regardless of their context
 impl PartialEq for TlvError {
     fn eq(&self, other: &Self) -> bool {
+        let (this, other) = (self.kind(), other.kind());
         // Mostly auto-generated by rust-analyzer
-        match (self, other) {
+        match (this, other) {
             (
                 Self::TypeMismatch {
                     expected: l_expected,
@@ -42,8 +322,42 @@ impl PartialEq for TlvError {
                     found: r_found,
                 },
             ) => l_expected == r_expected && l_found == r_found,
+            (Self::InvalidUri { reason: l }, Self::InvalidUri { reason: r }) => l == r,
+            (Self::InvalidKey { reason: l }, Self::InvalidKey { reason: r }) => l == r,
+            #[cfg(feature = "serde")]
+            (Self::Custom(l), Self::Custom(r)) => l == r,
+            #[cfg(feature = "std")]
             (Self::IOError(_), Self::IOError(_)) => true,
-            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+            _ => core::mem::discriminant(this) == core::mem::discriminant(other),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TlvError {
+    pub(crate) fn custom(msg: impl fmt::Display) -> Self {
+        use alloc::string::ToString;
+        TlvError::Custom(msg.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::ser::Error for TlvError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        TlvError::custom(msg)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::de::Error for TlvError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        TlvError::custom(msg)
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        match crate::ser::type_number(field) {
+            Ok(typ) => TlvError::MissingField { typ },
+            Err(_) => TlvError::custom(format_args!("missing field `{field}`")),
         }
     }
 }
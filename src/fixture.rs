@@ -0,0 +1,436 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use bytes::{Buf, BufMut, Bytes};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    encode_to_bytes, hex, looks_nested, ndn_type, ndn_type_by_name, GenericTlv, NdnType,
+    NonNegativeInteger, Result, TlvDecode, TlvDecodeRef, TlvEncode, ValueKind, VarNum,
+};
+
+/// A TLV record in a human-editable form, for use in test fixtures
+///
+/// Fixtures can be written in any format supported by serde, such as JSON or YAML. Each record is
+/// a map with a `type` and at most one value:
+///
+/// ```yaml
+/// - type: Name                 # symbolic name of a standard NDN type, or a number
+///   children:
+///     - type: 8
+///       text: hello            # UTF-8 string
+///     - type: 50
+///       nni: 3                 # NonNegativeInteger
+/// - type: 200
+///   hex: "0102ff"              # raw bytes
+/// ```
+///
+/// A record without a value is empty. A TLV-TYPE or TLV-LENGTH that is not minimally encoded is
+/// recorded as `type_size` or `length_size`, the number of bytes of its encoding.
+///
+/// Use [`TlvEncode`] to compile fixtures to wire bytes and [`TlvFixture::from_wire`] to convert
+/// wire bytes to fixtures. Converting wire bytes to a fixture and back always reproduces the
+/// original bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawFixture", into = "RawFixture")]
+pub struct TlvFixture {
+    /// TLV-TYPE
+    pub typ: FixtureType,
+    /// TLV-VALUE
+    pub value: FixtureValue,
+    /// The size of the encoded TLV-TYPE, if it is not the shortest possible
+    ///
+    /// The shortest valid encoding of at least this size is used.
+    pub type_size: Option<usize>,
+    /// The size of the encoded TLV-LENGTH, if it is not the shortest possible
+    ///
+    /// The shortest valid encoding of at least this size is used.
+    pub length_size: Option<usize>,
+}
+
+/// The TLV-TYPE of a [`TlvFixture`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FixtureType {
+    /// Written as a number
+    Number(usize),
+    /// Written as the name of a standard NDN type, see [`NDN_TYPES`](crate::NDN_TYPES)
+    Symbolic(&'static NdnType),
+}
+
+/// The TLV-VALUE of a [`TlvFixture`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FixtureValue {
+    /// Raw bytes, written as hex
+    Hex(Vec<u8>),
+    /// A UTF-8 string
+    Text(String),
+    /// A NonNegativeInteger, always using the shortest encoding
+    Nni(u64),
+    /// Nested records
+    Children(Vec<TlvFixture>),
+}
+
+impl FixtureType {
+    /// The type number
+    pub fn number(&self) -> usize {
+        match *self {
+            FixtureType::Number(typ) => typ,
+            FixtureType::Symbolic(info) => info.typ,
+        }
+    }
+}
+
+impl TlvFixture {
+    /// Convert the TLV record at the start of `bytes` to a fixture
+    ///
+    /// Standard NDN types are written by name if `symbolic` is true. Values are written as nested
+    /// records, NonNegativeIntegers or text where this is possible without losing information,
+    /// and as hex otherwise.
+    pub fn from_wire(bytes: &mut Bytes, symbolic: bool) -> Result<Self> {
+        let start = bytes.clone();
+        let tlv = GenericTlv::<Bytes>::decode(bytes)?;
+        let header_size = start.len() - bytes.len() - tlv.content.len();
+        let type_size = {
+            let mut cur = &start[..];
+            VarNum::decode_ref(&mut cur)?;
+            start.len() - cur.len()
+        };
+        let length_size = header_size - type_size;
+        let type_size = Some(type_size).filter(|&size| size != tlv.typ.size());
+        let length_size =
+            Some(length_size).filter(|&size| size != VarNum::from(tlv.content.len()).size());
+        let typ = usize::from(tlv.typ);
+        let info = ndn_type(typ);
+        let kind = info.map_or(ValueKind::Unknown, |t| t.kind);
+        let content = tlv.content;
+
+        let value = match kind {
+            _ if content.is_empty() => FixtureValue::Hex(Vec::new()),
+            ValueKind::Container | ValueKind::Unknown if looks_nested(&content) => {
+                match Self::all_from_wire(&mut content.clone(), symbolic) {
+                    Ok(children) if children.encode() == content => {
+                        FixtureValue::Children(children)
+                    }
+                    _ => Self::leaf(&content),
+                }
+            }
+            ValueKind::NonNegativeInteger => {
+                match NonNegativeInteger::decode(&mut content.clone()) {
+                    Ok(n) if n.is_minimal() => FixtureValue::Nni(n.as_u64()),
+                    _ => FixtureValue::Hex(content.to_vec()),
+                }
+            }
+            _ => Self::leaf(&content),
+        };
+        let typ = match info {
+            Some(info) if symbolic => FixtureType::Symbolic(info),
+            _ => FixtureType::Number(typ),
+        };
+        Ok(Self {
+            typ,
+            value,
+            type_size,
+            length_size,
+        })
+    }
+
+    /// Convert all TLV records in `bytes` to fixtures, see [`from_wire`](TlvFixture::from_wire)
+    pub fn all_from_wire(bytes: &mut Bytes, symbolic: bool) -> Result<Vec<Self>> {
+        let mut fixtures = Vec::new();
+        while bytes.has_remaining() {
+            fixtures.push(Self::from_wire(bytes, symbolic)?);
+        }
+        Ok(fixtures)
+    }
+
+    fn leaf(content: &[u8]) -> FixtureValue {
+        match core::str::from_utf8(content) {
+            Ok(text) if !text.chars().any(char::is_control) => FixtureValue::Text(text.into()),
+            _ => FixtureValue::Hex(content.to_vec()),
+        }
+    }
+
+    fn inner_size(&self) -> usize {
+        match &self.value {
+            FixtureValue::Hex(bytes) => bytes.len(),
+            FixtureValue::Text(text) => text.len(),
+            FixtureValue::Nni(n) => NonNegativeInteger::new(*n).size(),
+            FixtureValue::Children(children) => children.size(),
+        }
+    }
+}
+
+/// The size of the encoding of `value` as a VarNum with at least `size` bytes
+fn var_num_size(value: usize, size: Option<usize>) -> usize {
+    let min = VarNum::from(value).size();
+    [1, 3, 5, 9]
+        .into_iter()
+        .find(|&valid| valid >= min.max(size.unwrap_or(0)))
+        .unwrap_or(9)
+}
+
+/// Encode `value` as a VarNum with at least `size` bytes
+fn put_var_num(buf: &mut impl BufMut, value: usize, size: Option<usize>) {
+    match var_num_size(value, size) {
+        1 => buf.put_u8(value as u8),
+        3 => {
+            buf.put_u8(0xFD);
+            buf.put_u16(value as u16);
+        }
+        5 => {
+            buf.put_u8(0xFE);
+            buf.put_u32(value as u32);
+        }
+        _ => {
+            buf.put_u8(0xFF);
+            buf.put_u64(value as u64);
+        }
+    }
+}
+
+impl TlvEncode for TlvFixture {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        put_var_num(buf, self.typ.number(), self.type_size);
+        put_var_num(buf, self.inner_size(), self.length_size);
+        match &self.value {
+            FixtureValue::Hex(bytes) => buf.put_slice(bytes),
+            FixtureValue::Text(text) => buf.put_slice(text.as_bytes()),
+            FixtureValue::Nni(n) => NonNegativeInteger::new(*n).encode_to(buf),
+            FixtureValue::Children(children) => children.encode_to(buf),
+        }
+    }
+
+    fn size(&self) -> usize {
+        let inner_size = self.inner_size();
+        var_num_size(self.typ.number(), self.type_size)
+            + var_num_size(inner_size, self.length_size)
+            + inner_size
+    }
+}
+
+/// The serialized form of [`TlvFixture`]
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFixture {
+    #[serde(rename = "type")]
+    typ: RawType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nni: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<TlvFixture>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    type_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    length_size: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawType {
+    Number(usize),
+    Name(String),
+}
+
+/// An invalid [`TlvFixture`]
+#[derive(Debug)]
+struct FixtureError(&'static str);
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl TryFrom<RawFixture> for TlvFixture {
+    type Error = FixtureError;
+
+    fn try_from(raw: RawFixture) -> core::result::Result<Self, Self::Error> {
+        let typ = match raw.typ {
+            RawType::Number(typ) => FixtureType::Number(typ),
+            RawType::Name(name) => ndn_type_by_name(&name)
+                .map(FixtureType::Symbolic)
+                .ok_or(FixtureError("unknown TLV type name"))?,
+        };
+        let mut values = [
+            raw.hex.map(|hex| {
+                hex::decode(&hex)
+                    .map(FixtureValue::Hex)
+                    .map_err(FixtureError)
+            }),
+            raw.text.map(|text| Ok(FixtureValue::Text(text))),
+            raw.nni.map(|n| Ok(FixtureValue::Nni(n))),
+            raw.children.map(|c| Ok(FixtureValue::Children(c))),
+        ]
+        .into_iter()
+        .flatten();
+        let value = values.next().unwrap_or(Ok(FixtureValue::Hex(Vec::new())))?;
+        if values.next().is_some() {
+            return Err(FixtureError("TLV fixture has more than one value"));
+        }
+        for size in [raw.type_size, raw.length_size].into_iter().flatten() {
+            if ![1, 3, 5, 9].contains(&size) {
+                return Err(FixtureError("VarNum size must be 1, 3, 5 or 9"));
+            }
+        }
+        Ok(Self {
+            typ,
+            value,
+            type_size: raw.type_size,
+            length_size: raw.length_size,
+        })
+    }
+}
+
+impl From<TlvFixture> for RawFixture {
+    fn from(fixture: TlvFixture) -> Self {
+        let mut raw = RawFixture {
+            typ: match fixture.typ {
+                FixtureType::Number(typ) => RawType::Number(typ),
+                FixtureType::Symbolic(info) => RawType::Name(info.name.into()),
+            },
+            hex: None,
+            text: None,
+            nni: None,
+            children: None,
+            type_size: fixture.type_size,
+            length_size: fixture.length_size,
+        };
+        match fixture.value {
+            FixtureValue::Hex(bytes) if bytes.is_empty() => {}
+            FixtureValue::Hex(bytes) => raw.hex = Some(hex::encode(&bytes)),
+            FixtureValue::Text(text) => raw.text = Some(text),
+            FixtureValue::Nni(n) => raw.nni = Some(n),
+            FixtureValue::Children(children) => raw.children = Some(children),
+        }
+        raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    const DATA: &[u8] = &[
+        6, 21, 7, 8, 8, 2, b'h', b'i', 50, 2, 1, 0, 20, 3, 24, 1, 0, 200, 4, 0, 1, 0xFE, 0xFF,
+    ];
+
+    #[test]
+    fn round_trip() {
+        let fixtures = TlvFixture::all_from_wire(&mut Bytes::from_static(DATA), true).unwrap();
+        let json = serde_json::to_value(&fixtures).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "type": "Data",
+                "children": [
+                    {
+                        "type": "Name",
+                        "children": [
+                            { "type": "GenericNameComponent", "text": "hi" },
+                            { "type": "SegmentNameComponent", "nni": 256 },
+                        ],
+                    },
+                    {
+                        "type": "MetaInfo",
+                        "children": [{ "type": "ContentType", "nni": 0 }],
+                    },
+                    { "type": 200, "hex": "0001feff" },
+                ],
+            }])
+        );
+
+        let parsed: Vec<TlvFixture> = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, fixtures);
+        assert_eq!(parsed.encode(), DATA);
+    }
+
+    #[test]
+    fn numeric() {
+        let fixture = TlvFixture::from_wire(&mut Bytes::from_static(&[7, 0]), false).unwrap();
+        assert_eq!(
+            fixture,
+            TlvFixture {
+                typ: FixtureType::Number(7),
+                value: FixtureValue::Hex(vec![]),
+                type_size: None,
+                length_size: None,
+            }
+        );
+        assert_eq!(serde_json::to_string(&fixture).unwrap(), r#"{"type":7}"#);
+    }
+
+    #[test]
+    fn lossless() {
+        // A non-minimal NonNegativeInteger
+        let data = [0x18, 2, 0, 5];
+        let fixture = TlvFixture::from_wire(&mut Bytes::copy_from_slice(&data), true).unwrap();
+        assert_eq!(fixture.value, FixtureValue::Hex(vec![0, 5]));
+        assert_eq!(fixture.encode(), &data[..]);
+
+        // A container with a non-minimal length in a child
+        let data = [7, 4, 8, 0xFD, 0, 0];
+        let fixture = TlvFixture::from_wire(&mut Bytes::copy_from_slice(&data), true).unwrap();
+        assert_eq!(fixture.encode(), &data[..]);
+
+        // Non-minimal type and length of the outermost record
+        let data = [0xFD, 0, 8, 0xFE, 0, 0, 0, 1, b'a'];
+        let fixture = TlvFixture::from_wire(&mut Bytes::copy_from_slice(&data), false).unwrap();
+        assert_eq!((fixture.type_size, fixture.length_size), (Some(3), Some(5)));
+        assert_eq!(fixture.size(), data.len());
+        assert_eq!(fixture.encode(), &data[..]);
+        let json = serde_json::to_value(&fixture).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": 8, "text": "a", "type_size": 3, "length_size": 5 })
+        );
+        let parsed: TlvFixture = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.encode(), &data[..]);
+
+        // Text with control characters
+        let data = [200, 2, b'a', b'\n'];
+        let fixture = TlvFixture::from_wire(&mut Bytes::copy_from_slice(&data), true).unwrap();
+        assert_eq!(fixture.value, FixtureValue::Hex(vec![b'a', b'\n']));
+    }
+
+    #[test]
+    fn parse_text() {
+        let fixture: TlvFixture = serde_json::from_str(
+            r#"{"type": "Name", "children": [
+                {"type": 8, "text": "hello"},
+                {"type": 8, "hex": "01 02"},
+                {"type": 50, "nni": 70000},
+                {"type": 33}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            fixture.encode(),
+            &[
+                7, 19, 8, 5, b'h', b'e', b'l', b'l', b'o', 8, 2, 1, 2, 50, 4, 0, 1, 0x11, 0x70, 33,
+                0
+            ][..]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let parse = |json| serde_json::from_str::<TlvFixture>(json).unwrap_err();
+        parse(r#"{"type": "NoSuchType"}"#);
+        parse(r#"{"type": 8, "hex": "123"}"#);
+        parse(r#"{"type": 8, "hex": "zz"}"#);
+        parse(r#"{"type": 8, "text": "a", "nni": 1}"#);
+        parse(r#"{"type": 8, "value": "a"}"#);
+        parse(r#"{"type": 8, "length_size": 2}"#);
+    }
+}
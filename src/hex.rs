@@ -0,0 +1,26 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Encode `bytes` as lowercase hex digits
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+/// Decode hex digits, ignoring whitespace
+pub(crate) fn decode(hex: &str) -> Result<Vec<u8>, &'static str> {
+    let digits = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<Vec<_>>>()
+        .ok_or("invalid hex digit")?;
+    if digits.len() % 2 != 0 {
+        return Err("odd number of hex digits");
+    }
+    Ok(digits.chunks(2).map(|d| d[0] << 4 | d[1]).collect())
+}
@@ -0,0 +1,867 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Range;
+use core::time::Duration;
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::tlv::{decode_record, empty_record, split_record, value_record};
+use crate::{
+    decode_ordered, encode_to_bytes, ComponentType, Data, DecodeConfig, InterestSignatureInfo,
+    Name, NameComponent, NonNegativeInteger, Result, Tlv, TlvDecode, TlvEncode, TlvError,
+    UnknownTlvs, VarNum,
+};
+
+/// The lifetime of an Interest without an [`InterestLifetime`] element
+pub const DEFAULT_INTEREST_LIFETIME: Duration = Duration::from_secs(4);
+
+/// Allows the Interest to be satisfied by Data whose name has the Interest's name as a prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CanBePrefix;
+
+empty_record!(CanBePrefix, 33);
+
+/// Requests Data that has not exceeded its freshness period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MustBeFresh;
+
+empty_record!(MustBeFresh, 18);
+
+/// Names of producer regions towards which the Interest may be forwarded
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForwardingHint(pub Vec<Name>);
+
+value_record!(ForwardingHint, 30);
+
+/// A random value used to detect looping Interests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Nonce(pub [u8; 4]);
+
+value_record!(Nonce, 10);
+
+impl Nonce {
+    /// Generate a random nonce
+    ///
+    /// Panics if the operating system's random number generator fails.
+    #[cfg(feature = "std")]
+    pub fn random() -> Self {
+        let mut nonce = [0; 4];
+        getrandom::getrandom(&mut nonce).expect("failed to generate random nonce");
+        Self(nonce)
+    }
+}
+
+/// How long the Interest remains pending, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InterestLifetime(pub NonNegativeInteger);
+
+value_record!(InterestLifetime, 12);
+
+impl From<Duration> for InterestLifetime {
+    fn from(lifetime: Duration) -> Self {
+        Self(NonNegativeInteger::new(
+            lifetime.as_millis().try_into().unwrap_or(u64::MAX),
+        ))
+    }
+}
+
+impl From<InterestLifetime> for Duration {
+    fn from(lifetime: InterestLifetime) -> Self {
+        Duration::from_millis(lifetime.0.as_u64())
+    }
+}
+
+/// The number of hops the Interest may still be forwarded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HopLimit(pub u8);
+
+value_record!(HopLimit, 34);
+
+/// Arbitrary parameters for the producer
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApplicationParameters(pub Bytes);
+
+value_record!(ApplicationParameters, 36);
+
+/// The signature of a signed Interest
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InterestSignatureValue(pub Bytes);
+
+value_record!(InterestSignatureValue, 46);
+
+/// The encoding an Interest was decoded from
+///
+/// It is only used while the fields of the Interest are unchanged since decoding.
+#[derive(Clone)]
+pub struct InterestWire {
+    wire: Bytes,
+    /// The name components covered by the signature
+    signed_name: Vec<Range<usize>>,
+    /// The start of ApplicationParameters
+    parameters: Option<usize>,
+    /// The end of InterestSignatureInfo
+    signed_end: Option<usize>,
+    /// The Interest as decoded, without its wire
+    decoded: Box<Interest>,
+}
+
+impl InterestWire {
+    /// Locate the signed parts of `wire`, which `decoded` was decoded from
+    fn new(wire: Bytes, decoded: &Interest, config: &DecodeConfig) -> Result<Self> {
+        let (_, mut elements) = split_record(&mut wire.clone(), config)?;
+        let config = config.nested()?;
+        let len = wire.len();
+        let mut signed_name = Vec::new();
+        let mut parameters = None;
+        let mut signed_end = None;
+        while !elements.is_empty() {
+            let start = len - elements.len();
+            let (typ, mut inner) = split_record(&mut elements, &config)?;
+            let end = len - elements.len();
+            match usize::from(typ) {
+                Name::TYP => {
+                    while !inner.is_empty() {
+                        let start = end - inner.len();
+                        let (typ, _) = split_record(&mut inner, &config)?;
+                        if usize::from(typ) != ComponentType::ParametersSha256Digest.typ() {
+                            signed_name.push(start..end - inner.len());
+                        }
+                    }
+                }
+                ApplicationParameters::TYP => parameters = Some(start),
+                InterestSignatureInfo::TYP => signed_end = Some(end),
+                _ => {}
+            }
+        }
+        Ok(Self {
+            wire,
+            signed_name,
+            parameters,
+            signed_end,
+            decoded: Box::new(decoded.clone()),
+        })
+    }
+
+    /// The bytes the Interest was decoded from
+    pub fn bytes(&self) -> &Bytes {
+        &self.wire
+    }
+}
+
+impl fmt::Debug for InterestWire {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("InterestWire").field(&self.wire).finish()
+    }
+}
+
+/// An Interest packet, as defined by the NDN packet format v0.3
+///
+/// Elements are encoded in the order required by the specification. When decoding, elements that
+/// are out of order or duplicated lead to an error, as do unknown critical elements and a name
+/// that does not have exactly one ParametersSha256DigestComponent when ApplicationParameters are
+/// present, or has one when they are not. Unknown non-critical elements are kept in
+/// [`extra`](Interest::extra) and encoded where they appeared, so that the signature and
+/// parameters digest of a decoded Interest still cover them.
+///
+/// A decoded Interest also keeps the bytes it was decoded from in [`wire`](Interest::wire). While
+/// its other fields are unchanged, it encodes to exactly these bytes, and its signed portion and
+/// parameters digest are taken from them, so that non-minimal encodings stay valid. Equality and
+/// hashing ignore `wire`.
+#[derive(Debug, Clone, Default)]
+pub struct Interest {
+    /// The name of the requested Data
+    pub name: Name,
+    /// Whether the Interest can be satisfied by Data with a longer name
+    pub can_be_prefix: bool,
+    /// Whether the Interest can only be satisfied by fresh Data
+    pub must_be_fresh: bool,
+    /// Where the Interest may be forwarded
+    pub forwarding_hint: Option<ForwardingHint>,
+    /// Used to detect loops
+    pub nonce: Option<Nonce>,
+    /// How long the Interest remains pending, see [`Interest::lifetime`]
+    pub interest_lifetime: Option<InterestLifetime>,
+    /// How many more hops the Interest may be forwarded
+    pub hop_limit: Option<HopLimit>,
+    /// Parameters for the producer
+    pub application_parameters: Option<ApplicationParameters>,
+    /// Information about the signature of a signed Interest
+    pub signature_info: Option<InterestSignatureInfo>,
+    /// The signature of a signed Interest
+    pub signature_value: Option<InterestSignatureValue>,
+    /// Unknown non-critical elements
+    pub extra: UnknownTlvs,
+    /// The encoding the Interest was decoded from
+    pub wire: Option<InterestWire>,
+}
+
+impl Interest {
+    /// Create an Interest for `name`
+    ///
+    /// With the `std` feature, the Interest is given a random [`Nonce`]. Otherwise, a nonce should
+    /// be set before sending the Interest.
+    pub fn new(name: Name) -> Self {
+        Self {
+            name,
+            #[cfg(feature = "std")]
+            nonce: Some(Nonce::random()),
+            ..Default::default()
+        }
+    }
+
+    /// Set whether the Interest can be satisfied by Data with a longer name
+    pub fn with_can_be_prefix(mut self, can_be_prefix: bool) -> Self {
+        self.can_be_prefix = can_be_prefix;
+        self
+    }
+
+    /// Set whether the Interest can only be satisfied by fresh Data
+    pub fn with_must_be_fresh(mut self, must_be_fresh: bool) -> Self {
+        self.must_be_fresh = must_be_fresh;
+        self
+    }
+
+    /// Set how long the Interest remains pending
+    pub fn with_lifetime(mut self, lifetime: Duration) -> Self {
+        self.interest_lifetime = Some(lifetime.into());
+        self
+    }
+
+    /// Set how many hops the Interest may be forwarded
+    pub fn with_hop_limit(mut self, hop_limit: u8) -> Self {
+        self.hop_limit = Some(HopLimit(hop_limit));
+        self
+    }
+
+    /// Set the parameters for the producer
+    pub fn with_application_parameters(mut self, parameters: impl Into<Bytes>) -> Self {
+        self.application_parameters = Some(ApplicationParameters(parameters.into()));
+        self
+    }
+
+    /// Set the information about the signature
+    ///
+    /// The signature itself is added by [`sign`](Interest::sign), which keeps the nonce, time and
+    /// sequence number given here.
+    pub fn with_signature_info(mut self, info: InterestSignatureInfo) -> Self {
+        self.signature_info = Some(info);
+        self
+    }
+
+    /// How long the Interest remains pending
+    ///
+    /// This is [`DEFAULT_INTEREST_LIFETIME`] if no lifetime is set.
+    pub fn lifetime(&self) -> Duration {
+        self.interest_lifetime
+            .map_or(DEFAULT_INTEREST_LIFETIME, Duration::from)
+    }
+
+    /// Whether `data` satisfies the Interest
+    ///
+    /// The name of `data` must equal the Interest's name, or start with it if CanBePrefix is set.
+    /// An Interest name that ends in an ImplicitSha256DigestComponent and is one component longer
+    /// than the Data's name must instead equal its full name, including the implicit digest. As
+    /// computing that digest requires the `crypto` feature, such an Interest matches no Data
+    /// without it. With MustBeFresh, the Data must have a non-zero freshness period.
+    pub fn matches_data(&self, data: &Data) -> bool {
+        let name = data.name();
+        let name_matches = if self.name.len() == name.len() + 1 {
+            name.is_prefix_of(&self.name)
+                && self
+                    .name
+                    .get(name.len())
+                    .and_then(NameComponent::as_implicit_sha256_digest)
+                    .is_some_and(|digest| is_implicit_digest(digest, data))
+        } else if self.can_be_prefix {
+            self.name.is_prefix_of(name)
+        } else {
+            self.name == *name
+        };
+        name_matches && (!self.must_be_fresh || data.freshness_period() > Duration::ZERO)
+    }
+
+    /// The decoded wire, if the Interest has not been modified since
+    fn decoded_wire(&self) -> Option<&InterestWire> {
+        self.wire.as_ref().filter(|wire| *wire.decoded == *self)
+    }
+
+    /// The part of the Interest covered by its signature
+    ///
+    /// This is the encoding of the name components except any ParametersSha256DigestComponent,
+    /// followed by the elements from ApplicationParameters to the end of InterestSignatureInfo. For
+    /// an unmodified decoded Interest, these are slices of its original bytes.
+    pub fn signed_portion(&self) -> Bytes {
+        let mut bytes = BytesMut::new();
+        if let Some(wire) = self.decoded_wire() {
+            if let (Some(start), Some(end)) = (wire.parameters, wire.signed_end) {
+                for component in &wire.signed_name {
+                    bytes.put_slice(&wire.wire[component.clone()]);
+                }
+                bytes.put_slice(&wire.wire[start..end]);
+                return bytes.freeze();
+            }
+        }
+        for component in &self.name {
+            if component.component_type() != ComponentType::ParametersSha256Digest {
+                component.encode_to(&mut bytes);
+            }
+        }
+        self.application_parameters.encode_to(&mut bytes);
+        self.extra.encode_at(8, &mut bytes);
+        self.signature_info.encode_to(&mut bytes);
+        bytes.freeze()
+    }
+
+    /// The part of the Interest covered by the ParametersSha256DigestComponent, from the start of
+    /// ApplicationParameters to the end of the Interest
+    #[cfg(feature = "crypto")]
+    pub(crate) fn parameters_portion(&self) -> Bytes {
+        if let Some(wire) = self.decoded_wire() {
+            if let Some(start) = wire.parameters {
+                return wire.wire.slice(start..);
+            }
+        }
+        let mut bytes = BytesMut::new();
+        self.application_parameters.encode_to(&mut bytes);
+        self.extra.encode_at(8, &mut bytes);
+        self.signature_info.encode_to(&mut bytes);
+        self.extra.encode_at(9, &mut bytes);
+        self.signature_value.encode_to(&mut bytes);
+        self.extra.encode_at(10, &mut bytes);
+        bytes.freeze()
+    }
+
+    /// Check that the name has one ParametersSha256DigestComponent if the Interest has
+    /// ApplicationParameters and none otherwise, as ndn-cxx does when decoding
+    ///
+    /// Whether the digest matches is left to `verify_parameters_digest`.
+    fn check_parameters_digest(&self) -> Result<()> {
+        let typ = ComponentType::ParametersSha256Digest.typ();
+        let digests = self
+            .name
+            .iter()
+            .filter(|c| c.component_type() == ComponentType::ParametersSha256Digest)
+            .count();
+        match (self.application_parameters.is_some(), digests) {
+            (_, 2..) => Err(TlvError::DuplicateField { typ }),
+            (true, 0) => Err(TlvError::MissingField { typ }),
+            (false, 1) => Err(TlvError::MissingField {
+                typ: ApplicationParameters::TYP,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// The TLV-TYPEs of the elements of an Interest, in the order they must appear
+    const ELEMENTS: [usize; 10] = [
+        Name::TYP,
+        CanBePrefix::TYP,
+        MustBeFresh::TYP,
+        ForwardingHint::TYP,
+        Nonce::TYP,
+        InterestLifetime::TYP,
+        HopLimit::TYP,
+        ApplicationParameters::TYP,
+        InterestSignatureInfo::TYP,
+        InterestSignatureValue::TYP,
+    ];
+
+    fn decode_element(
+        &mut self,
+        typ: usize,
+        record: &mut Bytes,
+        config: &DecodeConfig,
+    ) -> Result<()> {
+        let (_, value) = split_record(&mut record.clone(), config)?;
+        let check_len = |len: usize| {
+            if value.len() == len {
+                Ok(())
+            } else {
+                Err(TlvError::UnexpectedLength)
+            }
+        };
+        match typ {
+            Name::TYP => self.name = Name::decode_with(record, config)?,
+            CanBePrefix::TYP => {
+                CanBePrefix::decode_with(record, config)?;
+                self.can_be_prefix = true;
+            }
+            MustBeFresh::TYP => {
+                MustBeFresh::decode_with(record, config)?;
+                self.must_be_fresh = true;
+            }
+            ForwardingHint::TYP => {
+                self.forwarding_hint = Some(ForwardingHint::decode_with(record, config)?)
+            }
+            Nonce::TYP => {
+                check_len(4)?;
+                self.nonce = Some(Nonce::decode_with(record, config)?);
+            }
+            InterestLifetime::TYP => {
+                self.interest_lifetime = Some(InterestLifetime::decode_with(record, config)?)
+            }
+            HopLimit::TYP => {
+                check_len(1)?;
+                self.hop_limit = Some(HopLimit::decode_with(record, config)?);
+            }
+            ApplicationParameters::TYP => {
+                self.application_parameters = Some(ApplicationParameters(value.clone()))
+            }
+            InterestSignatureInfo::TYP => {
+                self.signature_info = Some(InterestSignatureInfo::decode_with(record, config)?)
+            }
+            InterestSignatureValue::TYP => {
+                self.signature_value = Some(InterestSignatureValue(value.clone()))
+            }
+            _ => unreachable!("unknown elements are kept in extra"),
+        }
+        Ok(())
+    }
+}
+
+/// Whether `digest` is the implicit digest of `data`
+#[cfg(feature = "crypto")]
+fn is_implicit_digest(digest: &[u8; 32], data: &Data) -> bool {
+    *digest == data.implicit_digest()
+}
+
+/// Without the `crypto` feature, the implicit digest cannot be computed, so no digest matches
+#[cfg(not(feature = "crypto"))]
+fn is_implicit_digest(_: &[u8; 32], _: &Data) -> bool {
+    false
+}
+
+impl PartialEq for Interest {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.can_be_prefix == other.can_be_prefix
+            && self.must_be_fresh == other.must_be_fresh
+            && self.forwarding_hint == other.forwarding_hint
+            && self.nonce == other.nonce
+            && self.interest_lifetime == other.interest_lifetime
+            && self.hop_limit == other.hop_limit
+            && self.application_parameters == other.application_parameters
+            && self.signature_info == other.signature_info
+            && self.signature_value == other.signature_value
+            && self.extra == other.extra
+    }
+}
+
+impl Eq for Interest {}
+
+impl Hash for Interest {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.can_be_prefix.hash(state);
+        self.must_be_fresh.hash(state);
+        self.forwarding_hint.hash(state);
+        self.nonce.hash(state);
+        self.interest_lifetime.hash(state);
+        self.hop_limit.hash(state);
+        self.application_parameters.hash(state);
+        self.signature_info.hash(state);
+        self.signature_value.hash(state);
+        self.extra.hash(state);
+    }
+}
+
+impl Tlv for Interest {
+    const TYP: usize = 5;
+
+    fn inner_size(&self) -> usize {
+        self.name.size()
+            + self.can_be_prefix.then_some(CanBePrefix).size()
+            + self.must_be_fresh.then_some(MustBeFresh).size()
+            + self.forwarding_hint.size()
+            + self.nonce.size()
+            + self.interest_lifetime.size()
+            + self.hop_limit.size()
+            + self.application_parameters.size()
+            + self.signature_info.size()
+            + self.signature_value.size()
+            + self.extra.size()
+    }
+}
+
+impl TlvEncode for Interest {
+    fn encode(&self) -> Bytes {
+        if let Some(wire) = self.decoded_wire() {
+            return wire.wire.clone();
+        }
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        if let Some(wire) = self.decoded_wire() {
+            buf.put_slice(&wire.wire);
+            return;
+        }
+        VarNum::from(Self::TYP).encode_to(buf);
+        VarNum::from(self.inner_size()).encode_to(buf);
+        self.extra.encode_at(0, buf);
+        self.name.encode_to(buf);
+        self.extra.encode_at(1, buf);
+        self.can_be_prefix.then_some(CanBePrefix).encode_to(buf);
+        self.extra.encode_at(2, buf);
+        self.must_be_fresh.then_some(MustBeFresh).encode_to(buf);
+        self.extra.encode_at(3, buf);
+        self.forwarding_hint.encode_to(buf);
+        self.extra.encode_at(4, buf);
+        self.nonce.encode_to(buf);
+        self.extra.encode_at(5, buf);
+        self.interest_lifetime.encode_to(buf);
+        self.extra.encode_at(6, buf);
+        self.hop_limit.encode_to(buf);
+        self.extra.encode_at(7, buf);
+        self.application_parameters.encode_to(buf);
+        self.extra.encode_at(8, buf);
+        self.signature_info.encode_to(buf);
+        self.extra.encode_at(9, buf);
+        self.signature_value.encode_to(buf);
+        self.extra.encode_at(10, buf);
+    }
+
+    fn size(&self) -> usize {
+        if let Some(wire) = self.decoded_wire() {
+            return wire.wire.len();
+        }
+        let inner_size = self.inner_size();
+        VarNum::from(Self::TYP).size() + VarNum::from(inner_size).size() + inner_size
+    }
+}
+
+impl TlvDecode for Interest {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        let start_len = bytes.len();
+        let mut wire = bytes.clone();
+        let mut interest = decode_record(bytes, config, Some(Self::TYP), |_, mut elements| {
+            let config = config.nested()?;
+            let mut interest = Interest::default();
+            let mut has_name = false;
+            let mut extra = UnknownTlvs::new();
+            decode_ordered(
+                &mut elements,
+                &config,
+                &Self::ELEMENTS,
+                Some(&mut extra),
+                |typ, record| {
+                    if !has_name && typ != Name::TYP {
+                        return Err(TlvError::MissingField { typ: Name::TYP });
+                    }
+                    has_name = true;
+                    interest.decode_element(typ, record, &config)
+                },
+            )?;
+            interest.extra = extra;
+            if !has_name {
+                return Err(TlvError::MissingField { typ: Name::TYP });
+            }
+            interest.check_parameters_digest()?;
+            Ok(interest)
+        })
+        .map_err(|e| e.in_tlv(Some("Interest"), Self::TYP))?;
+        wire.truncate(start_len - bytes.len());
+        interest.wire = Some(InterestWire::new(wire, &interest, config)?);
+        Ok(interest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::SignatureType;
+
+    fn name(uri: &str) -> Name {
+        uri.parse().unwrap()
+    }
+
+    #[test]
+    fn minimal() {
+        let interest = Interest {
+            nonce: Some(Nonce([1, 2, 3, 4])),
+            ..Interest::new(name("/a"))
+        };
+        let wire = [5, 11, 7, 3, 8, 1, b'a', 10, 4, 1, 2, 3, 4];
+
+        assert_eq!(interest.encode(), &wire[..]);
+        assert_eq!(interest.size(), wire.len());
+        assert_eq!(
+            Interest::decode(&mut Bytes::copy_from_slice(&wire)).unwrap(),
+            interest
+        );
+        assert_eq!(interest.lifetime(), DEFAULT_INTEREST_LIFETIME);
+    }
+
+    // Encoded from the packet format specification. The digest component is the SHA-256 of
+    // `36, 3, 1, 2, 3`, computed with Python's hashlib rather than this crate.
+    #[test]
+    fn full() {
+        #[rustfmt::skip]
+        let wire: &[u8] = &[
+            5, 79,
+                7, 45,
+                    8, 3, b'n', b'd', b'n',
+                    8, 4, b't', b'e', b's', b't',
+                    2, 32,
+                        0xE6, 0xA1, 0x9F, 0xA8, 0xCA, 0x75, 0xE6, 0xAD,
+                        0x17, 0x95, 0xD3, 0x5E, 0xCF, 0x19, 0x98, 0x2A,
+                        0xEF, 0x3C, 0x46, 0xA8, 0xB8, 0xDB, 0x6B, 0x67,
+                        0x6A, 0xB4, 0x01, 0xC6, 0x47, 0xE2, 0x1A, 0xB4,
+                33, 0,
+                18, 0,
+                30, 8, 7, 6, 8, 4, b'h', b'i', b'n', b't',
+                10, 4, 0xDE, 0xAD, 0xBE, 0xEF,
+                12, 2, 0x07, 0xD0,
+                34, 1, 64,
+                36, 3, 1, 2, 3,
+        ];
+        let interest = Interest {
+            forwarding_hint: Some(ForwardingHint([name("/hint")].into())),
+            nonce: Some(Nonce([0xDE, 0xAD, 0xBE, 0xEF])),
+            ..Interest::new(name(
+                "/ndn/test/params-sha256=\
+                 e6a19fa8ca75e6ad1795d35ecf19982aef3c46a8b8db6b676ab401c647e21ab4",
+            ))
+            .with_can_be_prefix(true)
+            .with_must_be_fresh(true)
+            .with_lifetime(Duration::from_secs(2))
+            .with_hop_limit(64)
+            .with_application_parameters(&[1, 2, 3][..])
+        };
+
+        assert_eq!(interest.encode(), wire);
+        assert_eq!(
+            Interest::decode(&mut Bytes::from_static(wire)).unwrap(),
+            interest
+        );
+        assert_eq!(interest.lifetime(), Duration::from_secs(2));
+        #[cfg(feature = "crypto")]
+        assert!(interest.verify_parameters_digest());
+    }
+
+    #[test]
+    fn signed() {
+        #[rustfmt::skip]
+        let wire: &[u8] = &[
+            5, 52,
+                7, 37,
+                    8, 1, b'a',
+                    2, 32,
+                        0x3D, 0xFE, 0x42, 0xB4, 0x0C, 0xAF, 0x46, 0x1D,
+                        0x16, 0xBB, 0xB3, 0x73, 0xAD, 0xA8, 0xD8, 0xFF,
+                        0x4E, 0xA2, 0xBD, 0x01, 0xFC, 0x83, 0xF9, 0xE4,
+                        0xEC, 0xBD, 0x40, 0x80, 0x2E, 0xB6, 0xD5, 0xA8,
+                36, 0,
+                44, 3, 27, 1, 0,
+                46, 4, 1, 2, 3, 4,
+        ];
+        let interest = Interest::decode(&mut Bytes::from_static(wire)).unwrap();
+        assert_eq!(
+            interest.application_parameters,
+            Some(ApplicationParameters(Bytes::new()))
+        );
+        assert_eq!(
+            interest.signature_info,
+            Some(InterestSignatureInfo::new(SignatureType::DigestSha256))
+        );
+        assert_eq!(
+            interest.signature_value,
+            Some(InterestSignatureValue(Bytes::from_static(&[1, 2, 3, 4])))
+        );
+        assert_eq!(
+            interest.signed_portion(),
+            &[8, 1, b'a', 36, 0, 44, 3, 27, 1, 0][..]
+        );
+        assert_eq!(interest.encode(), wire);
+    }
+
+    #[test]
+    fn parameters_digest_component() {
+        // The digest itself is only checked by verify_parameters_digest
+        let interest = |digests: usize, parameters: bool| {
+            let mut name = alloc::vec![8, 1, b'a'];
+            for _ in 0..digests {
+                name.extend([2, 32]);
+                name.extend([0; 32]);
+            }
+            let mut value = alloc::vec![7, name.len() as u8];
+            value.extend(name);
+            if parameters {
+                value.extend([36, 0]);
+            }
+            let mut wire = alloc::vec![5, value.len() as u8];
+            wire.extend(value);
+            Interest::decode(&mut Bytes::from(wire))
+        };
+        assert!(interest(0, false).is_ok());
+        assert!(interest(1, true).is_ok());
+        assert_eq!(
+            interest(0, true).unwrap_err(),
+            TlvError::MissingField { typ: 2 }
+        );
+        assert_eq!(
+            interest(2, true).unwrap_err(),
+            TlvError::DuplicateField { typ: 2 }
+        );
+        assert_eq!(
+            interest(1, false).unwrap_err(),
+            TlvError::MissingField { typ: 36 }
+        );
+    }
+
+    #[test]
+    fn unknown_elements() {
+        // Non-critical elements are kept wherever they appear
+        let wire = [
+            5, 16, 7, 3, 8, 1, b'a', 200, 1, 0, 10, 4, 1, 2, 3, 4, 202, 0,
+        ];
+        let interest = Interest::decode(&mut Bytes::copy_from_slice(&wire)).unwrap();
+        assert_eq!(interest.nonce, Some(Nonce([1, 2, 3, 4])));
+        assert_eq!(interest.extra.len(), 2);
+        assert_eq!(interest.encode(), &wire[..]);
+        assert_eq!(interest.size(), wire.len());
+
+        // The signed portion includes those between the parameters and the signature info
+        #[rustfmt::skip]
+        let wire: &[u8] = &[
+            5, 55,
+                7, 37,
+                    8, 1, b'a',
+                    2, 32,
+                        0x88, 0xAB, 0x22, 0xFF, 0x2B, 0x88, 0xF0, 0x99,
+                        0x80, 0x17, 0xC2, 0x3C, 0x41, 0xCE, 0x99, 0xE4,
+                        0xA0, 0xA5, 0x17, 0x74, 0x30, 0x6C, 0xBB, 0x0B,
+                        0x2A, 0x23, 0xCC, 0xE7, 0x7E, 0x3E, 0x76, 0x23,
+                36, 1, 9,
+                200, 0,
+                44, 3, 27, 1, 0,
+                202, 0,
+                46, 0,
+                204, 0,
+        ];
+        let interest = Interest::decode(&mut Bytes::from_static(wire)).unwrap();
+        assert_eq!(interest.encode(), wire);
+        assert_eq!(
+            interest.signed_portion(),
+            &[8, 1, b'a', 36, 1, 9, 200, 0, 44, 3, 27, 1, 0][..]
+        );
+
+        let wire = [5, 8, 7, 3, 8, 1, b'a', 201, 1, 0];
+        let error = Interest::decode(&mut Bytes::copy_from_slice(&wire)).unwrap_err();
+        assert_eq!(error, TlvError::UnknownCritical { typ: 201 });
+        assert_eq!(error.context().unwrap().offset, Some(7));
+    }
+
+    #[test]
+    fn ordering() {
+        let cases: [(&[u8], TlvError); 9] = [
+            (
+                &[5, 9, 7, 3, 8, 1, b'a', 18, 0, 33, 0],
+                TlvError::OutOfOrder { typ: 33 },
+            ),
+            (
+                &[5, 9, 7, 3, 8, 1, b'a', 33, 0, 33, 0],
+                TlvError::DuplicateField { typ: 33 },
+            ),
+            (
+                &[5, 7, 33, 0, 7, 3, 8, 1, b'a'],
+                TlvError::MissingField { typ: 7 },
+            ),
+            (&[5, 0], TlvError::MissingField { typ: 7 }),
+            (
+                &[5, 8, 7, 3, 8, 1, b'a', 10, 1, 0],
+                TlvError::UnexpectedLength,
+            ),
+            (
+                &[5, 8, 7, 3, 8, 1, b'a', 33, 1, 0],
+                TlvError::UnexpectedLength,
+            ),
+            (
+                &[5, 8, 7, 3, 8, 1, b'a', 18, 1, 0],
+                TlvError::UnexpectedLength,
+            ),
+            (
+                &[5, 13, 7, 3, 8, 1, b'a', 30, 6, 7, 0, 201, 2, 1, 2],
+                TlvError::UnexpectedLength,
+            ),
+            (
+                &[6, 0],
+                TlvError::TypeMismatch {
+                    expected: 5,
+                    found: 6,
+                },
+            ),
+        ];
+        for (wire, error) in cases {
+            assert_eq!(
+                Interest::decode(&mut Bytes::copy_from_slice(wire)).unwrap_err(),
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn error_context() {
+        let wire = [5, 10, 7, 3, 8, 1, b'a', 12, 3, 0, 0, 0];
+        let error = Interest::decode(&mut Bytes::copy_from_slice(&wire)).unwrap_err();
+        assert_eq!(error, TlvError::UnexpectedLength);
+        assert_eq!(
+            error.to_string(),
+            "TLV Record had unexpected length at byte 9 in Interest(5) > InterestLifetime(12)"
+        );
+
+        let wire = [5, 9, 7, 3, 8, 1, b'a'];
+        let error = Interest::decode(&mut Bytes::copy_from_slice(&wire)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unexpected end of stream at byte 1 in Interest(5)"
+        );
+    }
+
+    #[test]
+    fn matches_data() {
+        let data = Data::new(name("/a/b")).with_freshness_period(Duration::from_secs(1));
+        let stale = Data::new(name("/a/b"));
+
+        let exact = Interest::new(name("/a/b"));
+        assert!(exact.matches_data(&data));
+        assert!(!exact.matches_data(&Data::new(name("/a/b/c"))));
+        assert!(!Interest::new(name("/a")).matches_data(&data));
+
+        let prefix = Interest::new(name("/a")).with_can_be_prefix(true);
+        assert!(prefix.matches_data(&data));
+        assert!(!prefix.matches_data(&Data::new(name("/b"))));
+
+        #[cfg(feature = "crypto")]
+        {
+            let digest = data.full_name();
+            assert!(Interest::new(digest.clone()).matches_data(&data));
+            assert!(!Interest::new(digest).matches_data(&stale));
+        }
+        let mut wrong = name("/a/b");
+        wrong.push(NameComponent::implicit_sha256_digest([0; 32]));
+        assert!(!Interest::new(wrong).matches_data(&data));
+        assert!(!Interest::new(name("/a/b/c")).matches_data(&data));
+
+        let fresh = Interest::new(name("/a/b")).with_must_be_fresh(true);
+        assert!(fresh.matches_data(&data));
+        assert!(!fresh.matches_data(&stale));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn defaults() {
+        let interest = Interest::new(name("/a"));
+        assert!(interest.nonce.is_some());
+        assert_eq!(interest.interest_lifetime, None);
+        assert_eq!(interest.lifetime(), Duration::from_secs(4));
+    }
+}
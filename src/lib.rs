@@ -1,30 +1,119 @@
 #![doc = include_str!("../README.md")]
 #![warn(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
 
 pub use ::bytes;
 pub use ::ndn_tlv_derive::Tlv;
 use bytes::{Buf, BufMut, Bytes, BytesMut};
-pub use error::TlvError;
+#[cfg(feature = "tokio")]
+pub use codec::{read_tlv_async, write_tlv_async, TlvCodec};
+pub use config::{DecodeConfig, DecodeLimits};
+#[cfg(all(feature = "crypto", feature = "std"))]
+pub use crypto::RsaSigner;
+#[cfg(feature = "crypto")]
+pub use crypto::{
+    DigestSha256, EcdsaP256Signer, EcdsaP256Verifier, Ed25519Signer, Ed25519Verifier, HmacSha256,
+    RsaVerifier,
+};
+pub use data::{Content, ContentType, Data, FinalBlockId, FreshnessPeriod, MetaInfo};
+#[cfg(feature = "serde")]
+pub use de::{from_bytes, from_bytes_with};
+pub use error::{ErrorContext, PathSegment, TlvError};
+#[cfg(feature = "serde")]
+pub use fixture::{FixtureType, FixtureValue, TlvFixture};
+pub use interest::{
+    ApplicationParameters, CanBePrefix, ForwardingHint, HopLimit, Interest, InterestLifetime,
+    InterestSignatureValue, InterestWire, MustBeFresh, Nonce, DEFAULT_INTEREST_LIFETIME,
+};
+pub use name::{ComponentType, Name, NameComponent};
+pub use ndn_types::{ndn_type, ndn_type_by_name, NdnType, ValueKind, NDN_TYPES};
+pub use node::{looks_nested, HexBytes, TlvNode, DEFAULT_MAX_NODE_DEPTH};
+pub use ordered::decode_ordered;
+#[cfg(feature = "std")]
+pub use reader::{TlvReader, DEFAULT_MAX_RECORD_SIZE};
+#[cfg(feature = "serde")]
+pub use ser::to_bytes;
+pub use signature::{
+    InterestSignatureInfo, KeyDigest, KeyLocator, NotAfter, NotBefore, SignatureInfo,
+    SignatureNonce, SignatureSeqNum, SignatureTime, SignatureType, SignatureValue, ValidityPeriod,
+};
+pub use signer::{Signer, Verifier};
 pub use tlv::{tlv_critical, tlv_typ_critical, GenericTlv, Tlv};
+pub use unknown::{UnknownTlv, UnknownTlvs};
+pub use unordered::{decode_unordered, required_field, set_unordered_field};
 pub use varnum::VarNum;
 
+#[cfg(feature = "tokio")]
+mod codec;
+mod config;
+#[cfg(feature = "crypto")]
+mod crypto;
+mod data;
+#[cfg(feature = "serde")]
+mod de;
 mod error;
+#[cfg(feature = "serde")]
+mod fixture;
+mod hex;
+mod interest;
+mod name;
+mod ndn_types;
+mod node;
+mod ordered;
+#[cfg(feature = "std")]
+mod reader;
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod signature;
+mod signer;
 mod tlv;
+mod unknown;
+mod unordered;
+mod uri;
 mod varnum;
 
 /// Common result type for library functions
-pub type Result<T> = std::result::Result<T, TlvError>;
+pub type Result<T> = core::result::Result<T, TlvError>;
 
 /// Encode data in TLV format
 ///
 /// The value is a TLV record, or part of one
+///
+/// Implementors must provide [`encode`](TlvEncode::encode). The default
+/// [`encode_to`](TlvEncode::encode_to) copies its result into the buffer; overriding `encode_to`
+/// allows nested values to be written into a single buffer without intermediate allocations, and
+/// such implementations can use [`encode_to_bytes`] for `encode`. Types using the derive macro rely
+/// on the default, so the records of the packet format implement `encode_to` by hand.
 pub trait TlvEncode {
     /// Encode the value as a TLV record or part of one
     fn encode(&self) -> Bytes;
+    /// Encode the value as a TLV record or part of one into an existing buffer
+    ///
+    /// Exactly [`size`](TlvEncode::size) bytes will be written to `buf`.
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_slice(&self.encode());
+    }
     /// The size of the encoded data in bytes
     fn size(&self) -> usize;
 }
 
+/// Encode `value` into a buffer of exactly [`size`](TlvEncode::size) bytes using
+/// [`encode_to`](TlvEncode::encode_to)
+///
+/// This is meant as the [`encode`](TlvEncode::encode) of types that implement `encode_to`
+/// themselves. It must not be used by types that rely on the default `encode_to`.
+pub fn encode_to_bytes<T: TlvEncode + ?Sized>(value: &T) -> Bytes {
+    let mut bytes = BytesMut::with_capacity(value.size());
+    value.encode_to(&mut bytes);
+    bytes.freeze()
+}
+
 /// Decode data in TLV format
 ///
 /// The value is a TLV record, or part of one
@@ -36,6 +125,44 @@ pub trait TlvDecode: Sized {
     /// data is known at the call site, restrict the size of `bytes` to prevent the entire buffer
     /// being consumed.
     fn decode(bytes: &mut Bytes) -> Result<Self>;
+
+    /// Decode the value from a `bytes::Buf` using the given configuration
+    ///
+    /// The default implementation checks the size of `bytes` against the total length limit and
+    /// then calls [`decode`](TlvDecode::decode), so strict mode and the other limits are not
+    /// applied. Implementations that decode numbers or nested values should override it and pass
+    /// `config` on to them.
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        config.check_total_length(bytes.remaining())?;
+        Self::decode(bytes)
+    }
+}
+
+/// Decode data in TLV format, borrowing from the input instead of copying it
+///
+/// The value is a TLV record, or part of one
+///
+/// The `Tlv` derive macro does not implement this trait. It is implemented for `&[u8]`, byte
+/// arrays, the integer types, [`VarNum`], [`NonNegativeInteger`], [`GenericTlv`], `Vec<T>` and
+/// `Option<T>`. Records made of other types need a hand-written implementation.
+pub trait TlvDecodeRef<'a>: Sized {
+    /// Decode the value from a byte slice
+    ///
+    /// The slice must be advanced to point behind the used data
+    /// The implementation may choose to consume a part, or the entire slice. If the length of the
+    /// data is known at the call site, restrict the size of `bytes` to prevent the entire slice
+    /// being consumed.
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self>;
+
+    /// Decode the value from a byte slice using the given configuration
+    ///
+    /// Borrowing counterpart to [`TlvDecode::decode_with`]. The default implementation checks the
+    /// size of `bytes` against the total length limit and then calls
+    /// [`decode_ref`](TlvDecodeRef::decode_ref).
+    fn decode_ref_with(bytes: &mut &'a [u8], config: &DecodeConfig) -> Result<Self> {
+        config.check_total_length(bytes.len())?;
+        Self::decode_ref(bytes)
+    }
 }
 
 /// A non-negative integer, not encoded using `VarNum`
@@ -62,10 +189,21 @@ impl Default for NonNegativeInteger {
 /// In `error_on_critical` is true, any unexpected critical TLV records of a different type will lead to an error.
 /// Unexpected non-critical TLV records will always be ignored.
 pub fn find_tlv<T: Tlv>(bytes: &mut Bytes, error_on_critical: bool) -> Result<()> {
+    find_tlv_with::<T>(bytes, error_on_critical, &DecodeConfig::default())
+}
+
+/// Advance `bytes` until a valid TLV record of type `T` is found, using the given configuration
+///
+/// See [`find_tlv`] for details.
+pub fn find_tlv_with<T: Tlv>(
+    bytes: &mut Bytes,
+    error_on_critical: bool,
+    config: &DecodeConfig,
+) -> Result<()> {
     let mut cur = bytes.clone();
 
     while cur.has_remaining() {
-        let found_typ = VarNum::decode(&mut cur)?;
+        let found_typ = VarNum::decode_with(&mut cur, config)?;
         if usize::from(found_typ) == T::TYP {
             return Ok(());
         }
@@ -79,7 +217,11 @@ pub fn find_tlv<T: Tlv>(bytes: &mut Bytes, error_on_critical: bool) -> Result<()
         }
 
         // non-critical
-        let length = VarNum::decode(&mut cur)?;
+        let length = VarNum::decode_with(&mut cur, config)?;
+        config.check_tlv_length(length.value())?;
+        if cur.remaining() < length.into() {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
         cur.advance(length.into());
         bytes.advance(bytes.remaining() - cur.remaining());
     }
@@ -87,24 +229,58 @@ pub fn find_tlv<T: Tlv>(bytes: &mut Bytes, error_on_critical: bool) -> Result<()
     Err(TlvError::UnexpectedEndOfStream)
 }
 
+/// Advance `bytes` until a valid TLV record of type `T` is found
+///
+/// Borrowing counterpart to [`find_tlv`], see there for details.
+pub fn find_tlv_ref<T: Tlv>(bytes: &mut &[u8], error_on_critical: bool) -> Result<()> {
+    let mut cur = *bytes;
+
+    while cur.has_remaining() {
+        let found_typ = VarNum::decode_ref(&mut cur)?;
+        if usize::from(found_typ) == T::TYP {
+            return Ok(());
+        }
+
+        // Wrong type
+        if error_on_critical && tlv_typ_critical(found_typ.into()) {
+            return Err(TlvError::TypeMismatch {
+                expected: T::TYP,
+                found: found_typ.into(),
+            });
+        }
+
+        // non-critical
+        let length = VarNum::decode_ref(&mut cur)?;
+        if cur.remaining() < length.into() {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        cur.advance(length.into());
+        *bytes = cur;
+    }
+
+    Err(TlvError::UnexpectedEndOfStream)
+}
+
 impl TlvEncode for NonNegativeInteger {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
         match *self {
             NonNegativeInteger::U8(n) => {
-                bytes.put_u8(n);
+                buf.put_u8(n);
             }
             NonNegativeInteger::U16(n) => {
-                bytes.put_u16(n);
+                buf.put_u16(n);
             }
             NonNegativeInteger::U32(n) => {
-                bytes.put_u32(n);
+                buf.put_u32(n);
             }
             NonNegativeInteger::U64(n) => {
-                bytes.put_u64(n);
+                buf.put_u64(n);
             }
         }
-        bytes.freeze()
     }
 
     fn size(&self) -> usize {
@@ -119,13 +295,35 @@ impl TlvEncode for NonNegativeInteger {
 
 impl TlvDecode for NonNegativeInteger {
     fn decode(bytes: &mut Bytes) -> Result<Self> {
-        match bytes.remaining() {
-            1 => Ok(Self::U8(bytes.get_u8())),
-            2 => Ok(Self::U16(bytes.get_u16())),
-            4 => Ok(Self::U32(bytes.get_u32())),
-            8 => Ok(Self::U64(bytes.get_u64())),
-            _ => Err(TlvError::UnexpectedLength),
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        let mut cur = &bytes[..];
+        let value = Self::decode_ref_with(&mut cur, config)?;
+        bytes.advance(bytes.len() - cur.len());
+        Ok(value)
+    }
+}
+
+impl<'a> TlvDecodeRef<'a> for NonNegativeInteger {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        Self::decode_ref_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_ref_with(bytes: &mut &'a [u8], config: &DecodeConfig) -> Result<Self> {
+        config.check_total_length(bytes.len())?;
+        let value = match bytes.remaining() {
+            1 => Self::U8(bytes.get_u8()),
+            2 => Self::U16(bytes.get_u16()),
+            4 => Self::U32(bytes.get_u32()),
+            8 => Self::U64(bytes.get_u64()),
+            _ => return Err(TlvError::UnexpectedLength),
+        };
+        if config.strict() && !value.is_minimal() {
+            return Err(TlvError::NonMinimalEncoding);
         }
+        Ok(value)
     }
 }
 
@@ -149,7 +347,7 @@ impl From<u32> for NonNegativeInteger {
 
 impl From<u64> for NonNegativeInteger {
     fn from(value: u64) -> Self {
-        Self::new(value as u64)
+        Self::new(value)
     }
 }
 
@@ -181,7 +379,17 @@ impl NonNegativeInteger {
         } else if value <= 0xFFFF_FFFF {
             NonNegativeInteger::U32(value as u32)
         } else {
-            NonNegativeInteger::U64(value as u64)
+            NonNegativeInteger::U64(value)
+        }
+    }
+
+    /// Whether this `NonNegativeInteger` uses the smallest possible representation for its value
+    pub const fn is_minimal(&self) -> bool {
+        match *self {
+            NonNegativeInteger::U8(_) => true,
+            NonNegativeInteger::U16(value) => value > 0xFF,
+            NonNegativeInteger::U32(value) => value > 0xFFFF,
+            NonNegativeInteger::U64(value) => value > 0xFFFF_FFFF,
         }
     }
 
@@ -191,7 +399,7 @@ impl NonNegativeInteger {
             NonNegativeInteger::U8(value) => value as u64,
             NonNegativeInteger::U16(value) => value as u64,
             NonNegativeInteger::U32(value) => value as u64,
-            NonNegativeInteger::U64(value) => value as u64,
+            NonNegativeInteger::U64(value) => value,
         }
     }
 
@@ -206,8 +414,8 @@ impl NonNegativeInteger {
     }
 }
 
-impl std::fmt::Display for NonNegativeInteger {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for NonNegativeInteger {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         u64::from(*self).fmt(f)
     }
 }
@@ -217,6 +425,10 @@ impl TlvEncode for Bytes {
         self.clone()
     }
 
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self);
+    }
+
     fn size(&self) -> usize {
         self.len()
     }
@@ -228,9 +440,35 @@ impl TlvDecode for Bytes {
     }
 }
 
+impl TlvEncode for &[u8] {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self);
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<'a> TlvDecodeRef<'a> for &'a [u8] {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        let ret = *bytes;
+        *bytes = &[];
+        Ok(ret)
+    }
+}
+
 impl<const N: usize> TlvEncode for [u8; N] {
     fn encode(&self) -> Bytes {
-        Bytes::copy_from_slice(&self[..])
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_slice(&self[..]);
     }
 
     fn size(&self) -> usize {
@@ -249,9 +487,24 @@ impl<const N: usize> TlvDecode for [u8; N] {
     }
 }
 
+impl<'a, const N: usize> TlvDecodeRef<'a> for [u8; N] {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < N {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        let mut buf = [0; N];
+        bytes.copy_to_slice(&mut buf);
+        Ok(buf)
+    }
+}
+
 impl TlvEncode for u8 {
     fn encode(&self) -> Bytes {
-        Bytes::copy_from_slice(&[*self][..])
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_u8(*self);
     }
 
     fn size(&self) -> usize {
@@ -268,9 +521,22 @@ impl TlvDecode for u8 {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for u8 {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < 1 {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        Ok(bytes.get_u8())
+    }
+}
+
 impl TlvEncode for i8 {
     fn encode(&self) -> Bytes {
-        Bytes::copy_from_slice(&[*self as u8][..])
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_i8(*self);
     }
 
     fn size(&self) -> usize {
@@ -287,11 +553,22 @@ impl TlvDecode for i8 {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for i8 {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < 1 {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        Ok(bytes.get_i8())
+    }
+}
+
 impl TlvEncode for u16 {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
-        bytes.put_u16(*self);
-        bytes.freeze()
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_u16(*self);
     }
 
     fn size(&self) -> usize {
@@ -308,11 +585,22 @@ impl TlvDecode for u16 {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for u16 {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < 2 {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        Ok(bytes.get_u16())
+    }
+}
+
 impl TlvEncode for i16 {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
-        bytes.put_i16(*self);
-        bytes.freeze()
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_i16(*self);
     }
 
     fn size(&self) -> usize {
@@ -329,11 +617,22 @@ impl TlvDecode for i16 {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for i16 {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < 2 {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        Ok(bytes.get_i16())
+    }
+}
+
 impl TlvEncode for u32 {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
-        bytes.put_u32(*self);
-        bytes.freeze()
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_u32(*self);
     }
 
     fn size(&self) -> usize {
@@ -350,11 +649,22 @@ impl TlvDecode for u32 {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for u32 {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < 4 {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        Ok(bytes.get_u32())
+    }
+}
+
 impl TlvEncode for i32 {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
-        bytes.put_i32(*self);
-        bytes.freeze()
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_i32(*self);
     }
 
     fn size(&self) -> usize {
@@ -371,11 +681,22 @@ impl TlvDecode for i32 {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for i32 {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < 4 {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        Ok(bytes.get_i32())
+    }
+}
+
 impl TlvEncode for u64 {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
-        bytes.put_u64(*self);
-        bytes.freeze()
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_u64(*self);
     }
 
     fn size(&self) -> usize {
@@ -392,11 +713,22 @@ impl TlvDecode for u64 {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for u64 {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < 8 {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        Ok(bytes.get_u64())
+    }
+}
+
 impl TlvEncode for i64 {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
-        bytes.put_i64(*self);
-        bytes.freeze()
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_i64(*self);
     }
 
     fn size(&self) -> usize {
@@ -413,11 +745,22 @@ impl TlvDecode for i64 {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for i64 {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < 8 {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        Ok(bytes.get_i64())
+    }
+}
+
 impl TlvEncode for u128 {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
-        bytes.put_u128(*self);
-        bytes.freeze()
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_u128(*self);
     }
 
     fn size(&self) -> usize {
@@ -434,11 +777,22 @@ impl TlvDecode for u128 {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for u128 {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < 16 {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        Ok(bytes.get_u128())
+    }
+}
+
 impl TlvEncode for i128 {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
-        bytes.put_i128(*self);
-        bytes.freeze()
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_i128(*self);
     }
 
     fn size(&self) -> usize {
@@ -455,13 +809,24 @@ impl TlvDecode for i128 {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for i128 {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        if bytes.remaining() < 16 {
+            return Err(TlvError::UnexpectedEndOfStream);
+        }
+        Ok(bytes.get_i128())
+    }
+}
+
 impl<T: TlvEncode> TlvEncode for Vec<T> {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
         for item in self {
-            bytes.put(item.encode());
+            item.encode_to(buf);
         }
-        bytes.freeze()
     }
 
     fn size(&self) -> usize {
@@ -474,25 +839,74 @@ impl<T: TlvEncode> TlvEncode for Vec<T> {
 
 impl<T: TlvDecode> TlvDecode for Vec<T> {
     fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        let len = bytes.len();
+        config.check_total_length(len)?;
         let mut ret = Vec::new();
         while bytes.has_remaining() {
             let remaining = bytes.remaining();
             let mut bytes_clone = bytes.clone();
-            let t = T::decode(&mut bytes_clone);
+            let t = T::decode_with(&mut bytes_clone, config);
             match t {
                 Ok(t) => {
+                    config
+                        .check_elements(ret.len() + 1)
+                        .map_err(|e| e.at(len - remaining).relative_to(len))?;
                     ret.push(t);
                     bytes.advance(remaining - bytes_clone.remaining());
                 }
-                Err(TlvError::TypeMismatch {
-                    expected: _,
-                    found: _,
-                }) => {
-                    // Different TLV than what we expected - Vec ended
-                    return Ok(ret);
+                Err(e) => match e.kind() {
+                    TlvError::TypeMismatch {
+                        expected: _,
+                        found: _,
+                    } => {
+                        // Different TLV than what we expected - Vec ended
+                        return Ok(ret);
+                    }
+                    // End of stream should not be possible unless the data is malformed
+                    _ => return Err(e.within(len - remaining, remaining).relative_to(len)),
+                },
+            }
+        }
+        Ok(ret)
+    }
+}
+
+impl<'a, T: TlvDecodeRef<'a>> TlvDecodeRef<'a> for Vec<T> {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        Self::decode_ref_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_ref_with(bytes: &mut &'a [u8], config: &DecodeConfig) -> Result<Self> {
+        let len = bytes.len();
+        config.check_total_length(len)?;
+        let mut ret = Vec::new();
+        while bytes.has_remaining() {
+            let remaining = bytes.remaining();
+            let mut bytes_clone = *bytes;
+            let t = T::decode_ref_with(&mut bytes_clone, config);
+            match t {
+                Ok(t) => {
+                    config
+                        .check_elements(ret.len() + 1)
+                        .map_err(|e| e.at(len - remaining).relative_to(len))?;
+                    ret.push(t);
+                    *bytes = bytes_clone;
                 }
-                // End of stream should not be possible unless the data is malformed
-                Err(e) => return Err(e),
+                Err(e) => match e.kind() {
+                    TlvError::TypeMismatch {
+                        expected: _,
+                        found: _,
+                    } => {
+                        // Different TLV than what we expected - Vec ended
+                        return Ok(ret);
+                    }
+                    // End of stream should not be possible unless the data is malformed
+                    _ => return Err(e.within(len - remaining, remaining).relative_to(len)),
+                },
             }
         }
         Ok(ret)
@@ -501,9 +915,12 @@ impl<T: TlvDecode> TlvDecode for Vec<T> {
 
 impl<T: TlvEncode> TlvEncode for Option<T> {
     fn encode(&self) -> Bytes {
-        match self {
-            None => Bytes::new(),
-            Some(value) => value.encode(),
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        if let Some(value) = self {
+            value.encode_to(buf);
         }
     }
 
@@ -517,31 +934,69 @@ impl<T: TlvEncode> TlvEncode for Option<T> {
 
 impl<T: TlvDecode> TlvDecode for Option<T> {
     fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        config.check_total_length(bytes.len())?;
         let remaining = bytes.remaining();
         let mut bytes_clone = bytes.clone();
-        let t = T::decode(&mut bytes_clone);
+        let t = T::decode_with(&mut bytes_clone, config);
         match t {
             Ok(value) => {
                 bytes.advance(remaining - bytes_clone.remaining());
                 Ok(Some(value))
             }
-            // Different Type - probably what is next to parse
-            Err(TlvError::TypeMismatch {
-                expected: _,
-                found: _,
-            }) => Ok(None),
-            // End of stream - no data here
-            Err(TlvError::UnexpectedEndOfStream) => Ok(None),
-            Err(e) => Err(e),
+            Err(e) => match e.kind() {
+                // Different Type - probably what is next to parse
+                TlvError::TypeMismatch {
+                    expected: _,
+                    found: _,
+                } => Ok(None),
+                // End of stream - no data here
+                TlvError::UnexpectedEndOfStream => Ok(None),
+                _ => Err(e.relative_to(remaining)),
+            },
+        }
+    }
+}
+
+impl<'a, T: TlvDecodeRef<'a>> TlvDecodeRef<'a> for Option<T> {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        Self::decode_ref_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_ref_with(bytes: &mut &'a [u8], config: &DecodeConfig) -> Result<Self> {
+        config.check_total_length(bytes.len())?;
+        let remaining = bytes.remaining();
+        let mut bytes_clone = *bytes;
+        let t = T::decode_ref_with(&mut bytes_clone, config);
+        match t {
+            Ok(value) => {
+                *bytes = bytes_clone;
+                Ok(Some(value))
+            }
+            Err(e) => match e.kind() {
+                // Different Type - probably what is next to parse
+                TlvError::TypeMismatch {
+                    expected: _,
+                    found: _,
+                } => Ok(None),
+                // End of stream - no data here
+                TlvError::UnexpectedEndOfStream => Ok(None),
+                _ => Err(e.relative_to(remaining)),
+            },
         }
     }
 }
 
 impl TlvEncode for () {
     fn encode(&self) -> Bytes {
-        Bytes::new()
+        encode_to_bytes(self)
     }
 
+    fn encode_to(&self, _: &mut impl BufMut) {}
+
     fn size(&self) -> usize {
         0
     }
@@ -553,8 +1008,16 @@ impl TlvDecode for () {
     }
 }
 
+impl<'a> TlvDecodeRef<'a> for () {
+    fn decode_ref(_: &mut &'a [u8]) -> Result<Self> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
     use super::*;
 
     #[derive(Debug, Eq, PartialEq, Tlv)]
@@ -563,6 +1026,26 @@ mod tests {
         pub(crate) name: Bytes,
     }
 
+    /// A non-critical record holding a number, used as a known field in record tests
+    #[derive(Debug, PartialEq, Eq, Tlv)]
+    #[tlv(105, internal = true)]
+    pub(crate) struct FaceId {
+        pub(crate) id: NonNegativeInteger,
+    }
+
+    /// A signer that always fails, used to check that packets are left unchanged
+    pub(crate) struct Failing;
+
+    impl Signer for Failing {
+        fn signature_type(&self) -> SignatureType {
+            SignatureType::Ed25519
+        }
+
+        fn sign(&self, _: &[u8]) -> Result<Bytes> {
+            Err(TlvError::SigningFailed)
+        }
+    }
+
     #[derive(Debug, Tlv, PartialEq)]
     #[tlv(7, internal = true)]
     struct Name {
@@ -739,6 +1222,106 @@ mod tests {
         assert_eq!(name.unwrap_err(), TlvError::UnexpectedEndOfStream);
     }
 
+    #[test]
+    fn non_negative_integer_strict() {
+        let strict = DecodeConfig::new().with_strict(true);
+        let cases: [(&[u8], bool); 8] = [
+            (&[0x00], true),
+            (&[0x00, 0xFF], false),
+            (&[0x01, 0x00], true),
+            (&[0x00, 0x00, 0xFF, 0xFF], false),
+            (&[0x00, 0x01, 0x00, 0x00], true),
+            (&[0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF], false),
+            (&[0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00], true),
+            (&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], true),
+        ];
+
+        for (data, minimal) in cases {
+            let lenient = NonNegativeInteger::decode(&mut Bytes::from(data)).unwrap();
+            assert_eq!(lenient.size(), data.len());
+            assert_eq!(lenient.is_minimal(), minimal);
+
+            let strict = NonNegativeInteger::decode_with(&mut Bytes::from(data), &strict);
+            if minimal {
+                assert_eq!(strict.unwrap(), lenient);
+            } else {
+                assert_eq!(strict.unwrap_err(), TlvError::NonMinimalEncoding);
+            }
+        }
+    }
+
+    #[test]
+    fn vec_strict() {
+        let strict = DecodeConfig::new().with_strict(true);
+        let mut data = Bytes::from(&[8, 1, b'a', 8, 0xFD, 0x00, 0x01, b'b'][..]);
+
+        let components = Vec::<GenericTlv<Bytes>>::decode_with(&mut data, &strict);
+        assert_eq!(components.unwrap_err(), TlvError::NonMinimalEncoding);
+    }
+
+    #[test]
+    fn decode_ref_strict() {
+        let strict = DecodeConfig::new().with_strict(true);
+        let data = [8, 1, b'a', 8, 0xFD, 0x00, 0x01, b'b'];
+
+        let components = Vec::<GenericTlv<&[u8]>>::decode_ref(&mut &data[..]).unwrap();
+        assert_eq!(components.len(), 2);
+        let components = Vec::<GenericTlv<&[u8]>>::decode_ref_with(&mut &data[..], &strict);
+        assert_eq!(components.unwrap_err(), TlvError::NonMinimalEncoding);
+
+        let number = NonNegativeInteger::decode_ref_with(&mut &[0x00, 0xFF][..], &strict);
+        assert_eq!(number.unwrap_err(), TlvError::NonMinimalEncoding);
+    }
+
+    /// A record whose borrowing decoder adds context to its errors, as nested decoders do
+    #[derive(Debug, PartialEq, Eq)]
+    struct ContextRef<'a>(&'a [u8]);
+
+    impl<'a> TlvDecodeRef<'a> for ContextRef<'a> {
+        fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+            Self::decode_ref_with(bytes, &DecodeConfig::default())
+        }
+
+        fn decode_ref_with(bytes: &mut &'a [u8], config: &DecodeConfig) -> Result<Self> {
+            let tlv = GenericTlv::<&[u8]>::decode_ref_with(bytes, config)
+                .map_err(|e| e.in_tlv(None, 8))?;
+            if tlv.typ.value() != 8 {
+                let found = tlv.typ.into();
+                return Err(TlvError::TypeMismatch { expected: 8, found }.in_tlv(None, 8));
+            }
+            Ok(Self(tlv.content))
+        }
+    }
+
+    #[test]
+    fn decode_ref_context() {
+        let data = [8, 1, b'a', 9, 0];
+        let mut slice = &data[..];
+        let records = Vec::<ContextRef>::decode_ref(&mut slice).unwrap();
+        assert_eq!(records, [ContextRef(b"a")]);
+        assert_eq!(Option::<ContextRef>::decode_ref(&mut slice).unwrap(), None);
+        assert_eq!(slice, [9, 0]);
+
+        let data = [8, 1, b'a', 8, 5, 1];
+        let error = Vec::<ContextRef>::decode_ref(&mut &data[..]).unwrap_err();
+        assert_eq!(error.kind(), &TlvError::UnexpectedEndOfStream);
+        assert_eq!(error.context().unwrap().offset, Some(3));
+    }
+
+    #[test]
+    fn decode_ref() {
+        let data = [126, 1, 0, 8, 2, 0x12, 0x34, 255];
+        let mut slice = &data[..];
+
+        find_tlv_ref::<GenericNameComponent>(&mut slice, true).unwrap();
+        assert_eq!(slice.len(), 5);
+        slice = &slice[2..];
+        assert_eq!(u16::decode_ref(&mut slice).unwrap(), 0x1234);
+        assert_eq!(Option::<u16>::decode_ref(&mut slice).unwrap(), None);
+        assert_eq!(<[u8; 1]>::decode_ref(&mut slice).unwrap(), [255]);
+        assert!(slice.is_empty());
+    }
+
     #[test]
     fn generic() {
         let mut data = Bytes::from(&[130, 3, 1, 2, 3][..]);
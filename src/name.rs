@@ -0,0 +1,817 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::tlv::decode_record;
+use crate::{
+    encode_to_bytes, DecodeConfig, NonNegativeInteger, Result, Tlv, TlvDecode, TlvEncode, TlvError,
+    VarNum,
+};
+
+/// The type of a [`NameComponent`], as assigned by the NDN packet format
+///
+/// Component types compare equal if their TLV-TYPE is equal, so `Other(8)` equals `Generic`.
+/// [`ComponentType::from_typ`] and the `From<usize>` conversion always return the named variant
+/// for an assigned number, which should be preferred when matching on component types.
+#[derive(Debug, Clone, Copy)]
+pub enum ComponentType {
+    /// ImplicitSha256DigestComponent (1), the SHA-256 digest of a whole Data packet
+    ImplicitSha256Digest,
+    /// ParametersSha256DigestComponent (2), the SHA-256 digest of an Interest's parameters
+    ParametersSha256Digest,
+    /// GenericNameComponent (8)
+    Generic,
+    /// KeywordNameComponent (32)
+    Keyword,
+    /// SegmentNameComponent (50)
+    Segment,
+    /// ByteOffsetNameComponent (52)
+    ByteOffset,
+    /// VersionNameComponent (54)
+    Version,
+    /// TimestampNameComponent (56), in microseconds since the UNIX epoch
+    Timestamp,
+    /// SequenceNumNameComponent (58)
+    SequenceNum,
+    /// Any other type
+    Other(usize),
+}
+
+impl PartialEq for ComponentType {
+    fn eq(&self, other: &Self) -> bool {
+        self.typ() == other.typ()
+    }
+}
+
+impl Eq for ComponentType {}
+
+impl Hash for ComponentType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.typ().hash(state);
+    }
+}
+
+impl ComponentType {
+    /// Look up the component type with the given TLV-TYPE
+    pub const fn from_typ(typ: usize) -> Self {
+        match typ {
+            1 => ComponentType::ImplicitSha256Digest,
+            2 => ComponentType::ParametersSha256Digest,
+            8 => ComponentType::Generic,
+            32 => ComponentType::Keyword,
+            50 => ComponentType::Segment,
+            52 => ComponentType::ByteOffset,
+            54 => ComponentType::Version,
+            56 => ComponentType::Timestamp,
+            58 => ComponentType::SequenceNum,
+            other => ComponentType::Other(other),
+        }
+    }
+
+    /// The TLV-TYPE of this component type
+    pub const fn typ(self) -> usize {
+        match self {
+            ComponentType::ImplicitSha256Digest => 1,
+            ComponentType::ParametersSha256Digest => 2,
+            ComponentType::Generic => 8,
+            ComponentType::Keyword => 32,
+            ComponentType::Segment => 50,
+            ComponentType::ByteOffset => 52,
+            ComponentType::Version => 54,
+            ComponentType::Timestamp => 56,
+            ComponentType::SequenceNum => 58,
+            ComponentType::Other(typ) => typ,
+        }
+    }
+}
+
+impl From<usize> for ComponentType {
+    fn from(typ: usize) -> Self {
+        Self::from_typ(typ)
+    }
+}
+
+impl From<ComponentType> for usize {
+    fn from(typ: ComponentType) -> Self {
+        typ.typ()
+    }
+}
+
+/// A single component of a [`Name`]
+///
+/// A component is a TLV record with a type between 1 and 65535 and an arbitrary value. The value
+/// is kept as `Bytes`, so decoding a name does not copy its components. Constructors and accessors
+/// are provided for the types assigned by the NDN packet format; components of other types can be
+/// created using [`new`](NameComponent::new).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NameComponent {
+    typ: VarNum,
+    value: Bytes,
+}
+
+impl NameComponent {
+    /// Create a component of arbitrary type
+    ///
+    /// Fails if `typ` is not between 1 and 65535, or if it is a digest component whose value is
+    /// not 32 bytes long.
+    pub fn new(typ: usize, value: impl Into<Bytes>) -> Result<Self> {
+        let component = Self {
+            typ: VarNum::from(typ),
+            value: value.into(),
+        };
+        component.validate()?;
+        Ok(component)
+    }
+
+    /// Create an ImplicitSha256DigestComponent
+    pub fn implicit_sha256_digest(digest: [u8; 32]) -> Self {
+        Self::new_unchecked(
+            ComponentType::ImplicitSha256Digest,
+            Bytes::copy_from_slice(&digest),
+        )
+    }
+
+    /// Create a ParametersSha256DigestComponent
+    pub fn parameters_sha256_digest(digest: [u8; 32]) -> Self {
+        Self::new_unchecked(
+            ComponentType::ParametersSha256Digest,
+            Bytes::copy_from_slice(&digest),
+        )
+    }
+
+    /// Create a GenericNameComponent
+    pub fn generic(value: impl Into<Bytes>) -> Self {
+        Self::new_unchecked(ComponentType::Generic, value.into())
+    }
+
+    /// Create a KeywordNameComponent
+    pub fn keyword(value: impl Into<Bytes>) -> Self {
+        Self::new_unchecked(ComponentType::Keyword, value.into())
+    }
+
+    /// Create a SegmentNameComponent
+    pub fn segment(segment: u64) -> Self {
+        Self::from_number(ComponentType::Segment, segment)
+    }
+
+    /// Create a ByteOffsetNameComponent
+    pub fn byte_offset(offset: u64) -> Self {
+        Self::from_number(ComponentType::ByteOffset, offset)
+    }
+
+    /// Create a VersionNameComponent
+    pub fn version(version: u64) -> Self {
+        Self::from_number(ComponentType::Version, version)
+    }
+
+    /// Create a TimestampNameComponent from microseconds since the UNIX epoch
+    pub fn timestamp(micros: u64) -> Self {
+        Self::from_number(ComponentType::Timestamp, micros)
+    }
+
+    /// Create a SequenceNumNameComponent
+    pub fn sequence_num(seq: u64) -> Self {
+        Self::from_number(ComponentType::SequenceNum, seq)
+    }
+
+    /// The TLV-TYPE of the component
+    pub fn typ(&self) -> VarNum {
+        self.typ
+    }
+
+    /// The type of the component
+    pub fn component_type(&self) -> ComponentType {
+        ComponentType::from_typ(self.typ.into())
+    }
+
+    /// The TLV-VALUE of the component
+    pub fn value(&self) -> &Bytes {
+        &self.value
+    }
+
+    /// The digest, if this is an ImplicitSha256DigestComponent
+    pub fn as_implicit_sha256_digest(&self) -> Option<&[u8; 32]> {
+        self.as_digest(ComponentType::ImplicitSha256Digest)
+    }
+
+    /// The digest, if this is a ParametersSha256DigestComponent
+    pub fn as_parameters_sha256_digest(&self) -> Option<&[u8; 32]> {
+        self.as_digest(ComponentType::ParametersSha256Digest)
+    }
+
+    /// The segment number, if this is a SegmentNameComponent holding a valid number
+    pub fn as_segment(&self) -> Option<u64> {
+        self.as_number(ComponentType::Segment)
+    }
+
+    /// The byte offset, if this is a ByteOffsetNameComponent holding a valid number
+    pub fn as_byte_offset(&self) -> Option<u64> {
+        self.as_number(ComponentType::ByteOffset)
+    }
+
+    /// The version, if this is a VersionNameComponent holding a valid number
+    pub fn as_version(&self) -> Option<u64> {
+        self.as_number(ComponentType::Version)
+    }
+
+    /// Microseconds since the UNIX epoch, if this is a TimestampNameComponent holding a valid number
+    pub fn as_timestamp(&self) -> Option<u64> {
+        self.as_number(ComponentType::Timestamp)
+    }
+
+    /// The sequence number, if this is a SequenceNumNameComponent holding a valid number
+    pub fn as_sequence_num(&self) -> Option<u64> {
+        self.as_number(ComponentType::SequenceNum)
+    }
+
+    /// The smallest component that is greater than this one in canonical order
+    ///
+    /// The value is incremented as a big-endian number. If every byte is 0xFF, the result is one
+    /// byte longer and all zeros instead, except for digest components, which cannot change their
+    /// length and continue with the smallest component of the next type.
+    pub fn successor(&self) -> Self {
+        let mut value = self.value.to_vec();
+        let overflow = match value.iter().rposition(|&byte| byte != 0xFF) {
+            Some(last) => {
+                value[last] += 1;
+                value[last + 1..].fill(0);
+                false
+            }
+            None => {
+                value.fill(0);
+                true
+            }
+        };
+        if !overflow {
+            return Self {
+                typ: self.typ,
+                value: value.into(),
+            };
+        }
+
+        match self.component_type() {
+            ComponentType::ImplicitSha256Digest => Self::parameters_sha256_digest([0; 32]),
+            ComponentType::ParametersSha256Digest => Self {
+                typ: VarNum::from(ComponentType::ParametersSha256Digest.typ() + 1),
+                value: Bytes::new(),
+            },
+            _ => {
+                value.push(0);
+                Self {
+                    typ: self.typ,
+                    value: value.into(),
+                }
+            }
+        }
+    }
+
+    fn new_unchecked(typ: ComponentType, value: Bytes) -> Self {
+        Self {
+            typ: VarNum::from(typ.typ()),
+            value,
+        }
+    }
+
+    fn from_number(typ: ComponentType, value: u64) -> Self {
+        Self::new_unchecked(typ, NonNegativeInteger::new(value).encode())
+    }
+
+    fn as_digest(&self, typ: ComponentType) -> Option<&[u8; 32]> {
+        if self.component_type() != typ {
+            return None;
+        }
+        self.value[..].try_into().ok()
+    }
+
+    fn as_number(&self, typ: ComponentType) -> Option<u64> {
+        if self.component_type() != typ {
+            return None;
+        }
+        NonNegativeInteger::decode(&mut self.value.clone())
+            .ok()
+            .map(u64::from)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(1..=0xFFFF).contains(&self.typ.value()) {
+            return Err(TlvError::FormatError);
+        }
+        match self.component_type() {
+            ComponentType::ImplicitSha256Digest | ComponentType::ParametersSha256Digest
+                if self.value.len() != 32 =>
+            {
+                Err(TlvError::UnexpectedLength)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Canonical order: by type, then by length of the value, then by the bytes of the value
+impl Ord for NameComponent {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.typ
+            .cmp(&other.typ)
+            .then_with(|| self.value.len().cmp(&other.value.len()))
+            .then_with(|| self.value.cmp(&other.value))
+    }
+}
+
+impl PartialOrd for NameComponent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl TlvEncode for NameComponent {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        self.typ.encode_to(buf);
+        VarNum::from(self.value.len()).encode_to(buf);
+        buf.put_slice(&self.value);
+    }
+
+    fn size(&self) -> usize {
+        self.typ.size() + VarNum::from(self.value.len()).size() + self.value.len()
+    }
+}
+
+impl TlvDecode for NameComponent {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        let start_len = bytes.len();
+        let component = decode_record(bytes, config, None, |typ, value| Ok(Self { typ, value }))?;
+        component.validate().map_err(|e| e.relative_to(start_len))?;
+        Ok(component)
+    }
+}
+
+/// An NDN name, consisting of a sequence of [`NameComponent`]s
+///
+/// Components are decoded without copying, so they share memory with the packet they came from.
+///
+/// Names are ordered canonically, comparing component by component, where a name is smaller than
+/// all names it is a proper prefix of. This matches the order used by ndn-cxx.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Name {
+    components: Vec<NameComponent>,
+}
+
+impl Name {
+    /// Create an empty name
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The components of the name
+    pub fn components(&self) -> &[NameComponent] {
+        &self.components
+    }
+
+    /// The component at `index`, if it exists
+    pub fn get(&self, index: usize) -> Option<&NameComponent> {
+        self.components.get(index)
+    }
+
+    /// The number of components
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Whether the name has no components
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Iterate over the components of the name
+    pub fn iter(&self) -> core::slice::Iter<'_, NameComponent> {
+        self.components.iter()
+    }
+
+    /// Add a component to the end of the name
+    pub fn push(&mut self, component: NameComponent) {
+        self.components.push(component);
+    }
+
+    /// Add a component to the end of the name, returning the name to allow chaining
+    pub fn append(&mut self, component: NameComponent) -> &mut Self {
+        self.push(component);
+        self
+    }
+
+    /// Whether this name is a prefix of `other`, or equal to it
+    pub fn is_prefix_of(&self, other: &Name) -> bool {
+        other.components.starts_with(&self.components)
+    }
+
+    /// The first `count` components of the name, or the whole name if it is shorter
+    ///
+    /// As in ndn-cxx, a negative `count` removes that many components from the end instead, so
+    /// `get_prefix(-1)` is the name without its last component. This returns an empty name if the
+    /// name has fewer components than are removed.
+    pub fn get_prefix(&self, count: isize) -> Name {
+        let count = match usize::try_from(count) {
+            Ok(count) => count,
+            Err(_) => self.len().saturating_sub(count.unsigned_abs()),
+        };
+        self.sub_name(0, count)
+    }
+
+    /// Up to `count` components, starting at component `start`
+    ///
+    /// Components beyond the end of the name are ignored, so this returns an empty name if `start`
+    /// is out of range.
+    pub fn sub_name(&self, start: usize, count: usize) -> Name {
+        Self {
+            components: self.components[self.range(start, count)].to_vec(),
+        }
+    }
+
+    /// Compare the sub name at `start` with up to `count` components to the sub name of `other`
+    /// at `other_start` with up to `other_count` components
+    ///
+    /// This is equivalent to comparing the results of [`sub_name`](Name::sub_name) in canonical
+    /// order, without copying any components, like `compare` in ndn-cxx.
+    pub fn compare(
+        &self,
+        start: usize,
+        count: usize,
+        other: &Name,
+        other_start: usize,
+        other_count: usize,
+    ) -> Ordering {
+        self.components[self.range(start, count)]
+            .cmp(&other.components[other.range(other_start, other_count)])
+    }
+
+    /// The indices of up to `count` components starting at `start`, limited to the name
+    fn range(&self, start: usize, count: usize) -> core::ops::Range<usize> {
+        let start = start.min(self.len());
+        start..start.saturating_add(count).min(self.len())
+    }
+
+    /// The smallest name that is greater than this one and not prefixed by it
+    ///
+    /// This replaces the last component with its [`successor`](NameComponent::successor). The
+    /// successor of the empty name is `/sha256digest=00...00`.
+    pub fn successor(&self) -> Name {
+        let Some((last, prefix)) = self.components.split_last() else {
+            return Self {
+                components: vec![NameComponent::implicit_sha256_digest([0; 32])],
+            };
+        };
+        let mut components = prefix.to_vec();
+        components.push(last.successor());
+        Self { components }
+    }
+}
+
+impl Extend<NameComponent> for Name {
+    fn extend<I: IntoIterator<Item = NameComponent>>(&mut self, iter: I) {
+        self.components.extend(iter);
+    }
+}
+
+impl From<Vec<NameComponent>> for Name {
+    fn from(components: Vec<NameComponent>) -> Self {
+        Self { components }
+    }
+}
+
+impl FromIterator<NameComponent> for Name {
+    fn from_iter<I: IntoIterator<Item = NameComponent>>(iter: I) -> Self {
+        Self {
+            components: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Name {
+    type Item = &'a NameComponent;
+    type IntoIter = core::slice::Iter<'a, NameComponent>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Tlv for Name {
+    const TYP: usize = 7;
+
+    fn inner_size(&self) -> usize {
+        self.components.size()
+    }
+}
+
+impl TlvEncode for Name {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        VarNum::from(Self::TYP).encode_to(buf);
+        VarNum::from(self.inner_size()).encode_to(buf);
+        self.components.encode_to(buf);
+    }
+
+    fn size(&self) -> usize {
+        let inner_size = self.inner_size();
+        VarNum::from(Self::TYP).size() + VarNum::from(inner_size).size() + inner_size
+    }
+}
+
+impl TlvDecode for Name {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        decode_record(bytes, config, Some(Self::TYP), |_, mut value| {
+            let len = value.len();
+            let mut decode_components = || -> Result<Vec<NameComponent>> {
+                let config = config.nested()?;
+                let mut components = Vec::new();
+                while value.has_remaining() {
+                    let remaining = value.remaining();
+                    let position = len - remaining;
+                    config
+                        .check_elements(components.len() + 1)
+                        .map_err(|e| e.at(position))?;
+                    components.push(
+                        NameComponent::decode_with(&mut value, &config)
+                            .map_err(|e| e.within(position, remaining))?,
+                    );
+                }
+                Ok(components)
+            };
+            let components = decode_components().map_err(|e| e.in_field("components"))?;
+            Ok(Self { components })
+        })
+        .map_err(|e| e.in_tlv(Some("Name"), Self::TYP))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn component_types() {
+        let digest = [0xAB; 32];
+        let cases = [
+            (NameComponent::implicit_sha256_digest(digest), 1),
+            (NameComponent::parameters_sha256_digest(digest), 2),
+            (NameComponent::generic(&b"hello"[..]), 8),
+            (NameComponent::keyword(&b"key"[..]), 32),
+            (NameComponent::segment(3), 50),
+            (NameComponent::byte_offset(0x1234), 52),
+            (NameComponent::version(1_700_000_000), 54),
+            (NameComponent::timestamp(0), 56),
+            (NameComponent::sequence_num(u64::MAX), 58),
+            (NameComponent::new(200, &b"x"[..]).unwrap(), 200),
+        ];
+
+        for (component, typ) in cases {
+            assert_eq!(usize::from(component.typ()), typ);
+            assert_eq!(component.component_type().typ(), typ);
+            assert_eq!(ComponentType::from_typ(typ), component.component_type());
+
+            let encoded = component.encode();
+            assert_eq!(encoded.len(), component.size());
+            let decoded = NameComponent::decode(&mut encoded.clone()).unwrap();
+            assert_eq!(decoded, component);
+        }
+
+        // Assigned numbers are equal to their named variant
+        assert_eq!(ComponentType::Other(8), ComponentType::Generic);
+        assert_ne!(ComponentType::Other(9), ComponentType::Generic);
+        assert!(matches!(ComponentType::from(8), ComponentType::Generic));
+    }
+
+    #[test]
+    fn component_accessors() {
+        let segment = NameComponent::segment(0x1234);
+        assert_eq!(segment.value(), &[0x12, 0x34][..]);
+        assert_eq!(segment.as_segment(), Some(0x1234));
+        assert_eq!(segment.as_version(), None);
+        assert_eq!(NameComponent::version(7).as_version(), Some(7));
+        assert_eq!(NameComponent::byte_offset(7).as_byte_offset(), Some(7));
+        assert_eq!(NameComponent::timestamp(7).as_timestamp(), Some(7));
+        assert_eq!(NameComponent::sequence_num(7).as_sequence_num(), Some(7));
+
+        // Not a valid NonNegativeInteger
+        let segment = NameComponent::new(50, &[1, 2, 3][..]).unwrap();
+        assert_eq!(segment.component_type(), ComponentType::Segment);
+        assert_eq!(segment.as_segment(), None);
+
+        let digest = NameComponent::implicit_sha256_digest([7; 32]);
+        assert_eq!(digest.as_implicit_sha256_digest(), Some(&[7; 32]));
+        assert_eq!(digest.as_parameters_sha256_digest(), None);
+        assert_eq!(NameComponent::generic(&b"a"[..]).as_segment(), None);
+    }
+
+    #[test]
+    fn component_invalid() {
+        assert_eq!(
+            NameComponent::new(0, Bytes::new()).unwrap_err(),
+            TlvError::FormatError
+        );
+        assert_eq!(
+            NameComponent::new(0x10000, Bytes::new()).unwrap_err(),
+            TlvError::FormatError
+        );
+        assert_eq!(
+            NameComponent::new(1, &[0; 31][..]).unwrap_err(),
+            TlvError::UnexpectedLength
+        );
+
+        let mut data = Bytes::from_static(&[2, 1, 0]);
+        assert_eq!(
+            NameComponent::decode(&mut data).unwrap_err(),
+            TlvError::UnexpectedLength
+        );
+    }
+
+    #[test]
+    fn name() {
+        let wire = Bytes::from_static(&[
+            7, 14, 8, 5, b'h', b'e', b'l', b'l', b'o', 50, 1, 3, 0xFD, 0x01, 0x00, 0, 255,
+        ]);
+        let mut data = wire.clone();
+        let name = Name::decode(&mut data).unwrap();
+
+        assert_eq!(data.remaining(), 1);
+        assert_eq!(name.len(), 3);
+        assert_eq!(name.components()[0], NameComponent::generic(&b"hello"[..]));
+        assert_eq!(name.get(1).unwrap().as_segment(), Some(3));
+        assert_eq!(usize::from(name.get(2).unwrap().typ()), 0x100);
+        assert!(name.get(3).is_none());
+
+        let mut built = Name::new();
+        built.push(NameComponent::generic(&b"hello"[..]));
+        built.push(NameComponent::segment(3));
+        built.push(NameComponent::new(0x100, Bytes::new()).unwrap());
+        assert_eq!(built, name);
+        assert_eq!(built.encode(), wire.slice(..16));
+    }
+
+    #[test]
+    fn name_encode() {
+        let name: Name = [NameComponent::generic(&b"a"[..]), NameComponent::version(1)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(name.encode(), &[7, 6, 8, 1, b'a', 54, 1, 1][..]);
+        assert_eq!(name.size(), 8);
+        assert_eq!(name.inner_size(), 6);
+        assert_eq!(Name::new().encode(), &[7, 0][..]);
+        assert_eq!(
+            Name::decode(&mut Bytes::from_static(&[7, 0])).unwrap(),
+            Name::new()
+        );
+        assert_eq!(
+            name.iter()
+                .map(NameComponent::component_type)
+                .collect::<Vec<_>>(),
+            vec![ComponentType::Generic, ComponentType::Version]
+        );
+    }
+
+    #[test]
+    fn component_order() {
+        // Sorted canonically
+        let components = [
+            NameComponent::implicit_sha256_digest([0xFF; 32]),
+            NameComponent::parameters_sha256_digest([0; 32]),
+            NameComponent::generic(Bytes::new()),
+            NameComponent::generic(&[0xFF][..]),
+            NameComponent::generic(&[0, 0][..]),
+            NameComponent::generic(&[0, 1][..]),
+            NameComponent::generic(&[1, 0][..]),
+            NameComponent::keyword(Bytes::new()),
+            NameComponent::segment(0),
+            NameComponent::segment(0x100),
+            NameComponent::new(0x100, Bytes::new()).unwrap(),
+        ];
+        for (i, a) in components.iter().enumerate() {
+            for (j, b) in components.iter().enumerate() {
+                assert_eq!(a.cmp(b), i.cmp(&j), "{a} <=> {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn component_successor() {
+        let cases = [
+            (NameComponent::generic(Bytes::new()), "%00"),
+            (NameComponent::generic(&b"a"[..]), "b"),
+            (NameComponent::generic(&[0x01, 0xFF][..]), "%02%00"),
+            (NameComponent::generic(&[0xFF, 0xFF][..]), "%00%00%00"),
+            (NameComponent::segment(255), "50=%00%00"),
+            (
+                NameComponent::implicit_sha256_digest([0xFF; 32]),
+                "params-sha256=0000000000000000000000000000000000000000000000000000000000000000",
+            ),
+            (NameComponent::parameters_sha256_digest([0xFF; 32]), "3=..."),
+        ];
+        for (component, successor) in cases {
+            assert_eq!(component.successor().to_string(), successor);
+            assert!(component.successor() > component);
+        }
+
+        let digest = NameComponent::implicit_sha256_digest([0; 32]).successor();
+        assert_eq!(digest.as_implicit_sha256_digest().unwrap()[31], 1);
+    }
+
+    #[test]
+    fn name_order() {
+        let names = ["/", "/a", "/a/b", "/a/b/c", "/a/c", "/b", "/aa", "/32=a"];
+        for (i, a) in names.iter().enumerate() {
+            for (j, b) in names.iter().enumerate() {
+                let (a, b) = (a.parse::<Name>().unwrap(), b.parse::<Name>().unwrap());
+                assert_eq!(a.cmp(&b), i.cmp(&j), "{a} <=> {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn name_algebra() {
+        let name: Name = "/a/b/c".parse().unwrap();
+        let prefix: Name = "/a/b".parse().unwrap();
+
+        assert!(prefix.is_prefix_of(&name));
+        assert!(name.is_prefix_of(&name));
+        assert!(Name::new().is_prefix_of(&name));
+        assert!(!name.is_prefix_of(&prefix));
+        assert!(!"/a/c".parse::<Name>().unwrap().is_prefix_of(&name));
+
+        assert_eq!(name.get_prefix(2), prefix);
+        assert_eq!(name.get_prefix(0), Name::new());
+        assert_eq!(name.get_prefix(5), name);
+        assert_eq!(name.get_prefix(-1), prefix);
+        assert_eq!(name.get_prefix(-3), Name::new());
+        assert_eq!(name.get_prefix(-5), Name::new());
+        assert_eq!(name.get_prefix(isize::MIN), Name::new());
+        assert_eq!(name.sub_name(1, 1).to_string(), "/b");
+        assert_eq!(name.sub_name(1, usize::MAX).to_string(), "/b/c");
+        assert_eq!(name.sub_name(4, 1), Name::new());
+
+        assert_eq!(name.compare(0, 2, &prefix, 0, 2), Ordering::Equal);
+        assert_eq!(
+            name.compare(0, usize::MAX, &prefix, 0, 2),
+            Ordering::Greater
+        );
+        assert_eq!(name.compare(1, 1, &prefix, 1, 5), Ordering::Equal);
+        assert_eq!(name.compare(2, 1, &prefix, 1, 1), Ordering::Greater);
+        assert_eq!(name.compare(4, 1, &prefix, 0, 0), Ordering::Equal);
+        assert_eq!(Name::new().compare(0, 0, &prefix, 0, 1), Ordering::Less);
+
+        let mut built = Name::new();
+        built
+            .append(NameComponent::generic(&b"a"[..]))
+            .append(NameComponent::generic(&b"b"[..]));
+        assert_eq!(built, prefix);
+        built.extend(name.sub_name(2, 1).iter().cloned());
+        assert_eq!(built, name);
+
+        assert_eq!(name.successor().to_string(), "/a/b/d");
+        assert!(name.successor() > name);
+        assert!(!name.is_prefix_of(&name.successor()));
+        assert_eq!(
+            Name::new().successor().to_string(),
+            "/sha256digest=0000000000000000000000000000000000000000000000000000000000000000"
+        );
+    }
+
+    #[test]
+    fn name_errors() {
+        let mut data = Bytes::from_static(&[8, 0]);
+        assert_eq!(
+            Name::decode(&mut data).unwrap_err(),
+            TlvError::TypeMismatch {
+                expected: 7,
+                found: 8
+            }
+        );
+
+        let mut data = Bytes::from_static(&[7, 5, 8, 1, b'a', 0, 0]);
+        let error = Name::decode(&mut data).unwrap_err();
+        assert_eq!(error, TlvError::FormatError);
+        assert_eq!(
+            error.to_string(),
+            "Data had unexpected format at byte 5 in Name(7) > components"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+/// What the value of a TLV record contains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    /// A sequence of nested TLV records
+    Container,
+    /// A [`NonNegativeInteger`](crate::NonNegativeInteger)
+    NonNegativeInteger,
+    /// Opaque bytes
+    Bytes,
+    /// Arbitrary content, which may or may not consist of TLV records
+    Unknown,
+}
+
+/// A TLV-TYPE assigned by the NDN packet format or link protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NdnType {
+    /// The type number
+    pub typ: usize,
+    /// The name used by the specification
+    pub name: &'static str,
+    /// What the value of the record contains
+    pub kind: ValueKind,
+}
+
+const fn t(typ: usize, name: &'static str, kind: ValueKind) -> NdnType {
+    NdnType { typ, name, kind }
+}
+
+/// TLV-TYPEs assigned by the NDN packet format v0.3 and NDNLPv2
+pub const NDN_TYPES: &[NdnType] = {
+    use ValueKind::*;
+    &[
+        t(0x01, "ImplicitSha256DigestComponent", Bytes),
+        t(0x02, "ParametersSha256DigestComponent", Bytes),
+        t(0x05, "Interest", Container),
+        t(0x06, "Data", Container),
+        t(0x07, "Name", Container),
+        t(0x08, "GenericNameComponent", Bytes),
+        t(0x0A, "Nonce", Bytes),
+        t(0x0C, "InterestLifetime", NonNegativeInteger),
+        t(0x12, "MustBeFresh", Bytes),
+        t(0x14, "MetaInfo", Container),
+        t(0x15, "Content", Unknown),
+        t(0x16, "SignatureInfo", Container),
+        t(0x17, "SignatureValue", Bytes),
+        t(0x18, "ContentType", NonNegativeInteger),
+        t(0x19, "FreshnessPeriod", NonNegativeInteger),
+        t(0x1A, "FinalBlockId", Container),
+        t(0x1B, "SignatureType", NonNegativeInteger),
+        t(0x1C, "KeyLocator", Container),
+        t(0x1D, "KeyDigest", Bytes),
+        t(0x1E, "ForwardingHint", Container),
+        t(0x20, "KeywordNameComponent", Bytes),
+        t(0x21, "CanBePrefix", Bytes),
+        t(0x22, "HopLimit", NonNegativeInteger),
+        t(0x24, "ApplicationParameters", Unknown),
+        t(0x26, "SignatureNonce", Bytes),
+        t(0x28, "SignatureTime", NonNegativeInteger),
+        t(0x2A, "SignatureSeqNum", NonNegativeInteger),
+        t(0x2C, "InterestSignatureInfo", Container),
+        t(0x2E, "InterestSignatureValue", Bytes),
+        t(0x32, "SegmentNameComponent", NonNegativeInteger),
+        t(0x34, "ByteOffsetNameComponent", NonNegativeInteger),
+        t(0x36, "VersionNameComponent", NonNegativeInteger),
+        t(0x38, "TimestampNameComponent", NonNegativeInteger),
+        t(0x3A, "SequenceNumNameComponent", NonNegativeInteger),
+        t(0x50, "LpFragment", Unknown),
+        t(0x51, "LpSequence", NonNegativeInteger),
+        t(0x52, "FragIndex", NonNegativeInteger),
+        t(0x53, "FragCount", NonNegativeInteger),
+        t(0x62, "PitToken", Bytes),
+        t(0x64, "LpPacket", Container),
+        t(0xFD, "ValidityPeriod", Container),
+        t(0xFE, "NotBefore", Bytes),
+        t(0xFF, "NotAfter", Bytes),
+        t(0x0320, "Nack", Container),
+        t(0x0321, "NackReason", NonNegativeInteger),
+    ]
+};
+
+/// Look up a standard NDN TLV-TYPE by number
+pub fn ndn_type(typ: usize) -> Option<&'static NdnType> {
+    NDN_TYPES.iter().find(|t| t.typ == typ)
+}
+
+/// Look up a standard NDN TLV-TYPE by its name in the specification
+pub fn ndn_type_by_name(name: &str) -> Option<&'static NdnType> {
+    NDN_TYPES.iter().find(|t| t.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        assert_eq!(ndn_type(7).unwrap().name, "Name");
+        assert_eq!(ndn_type_by_name("Data").unwrap().typ, 6);
+        assert_eq!(ndn_type(0x18).unwrap().kind, ValueKind::NonNegativeInteger);
+        assert_eq!(ndn_type(200), None);
+        assert_eq!(ndn_type_by_name("Unknown"), None);
+    }
+
+    #[test]
+    fn unique() {
+        for (i, a) in NDN_TYPES.iter().enumerate() {
+            for b in &NDN_TYPES[i + 1..] {
+                assert_ne!(a.typ, b.typ);
+                assert_ne!(a.name, b.name);
+            }
+        }
+    }
+}
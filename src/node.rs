@@ -0,0 +1,463 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::tlv::decode_record;
+use crate::{encode_to_bytes, DecodeConfig, Result, TlvDecode, TlvDecodeRef, TlvEncode, VarNum};
+
+/// The maximum nesting depth of a [`TlvNode`] if the [`DecodeConfig`] does not set one
+///
+/// Parsing recurses into each nested record, so without a limit, hostile input of a few kilobytes
+/// could exhaust the stack.
+pub const DEFAULT_MAX_NODE_DEPTH: usize = 32;
+
+/// A TLV record of arbitrary type, parsed recursively
+///
+/// Unlike [`GenericTlv`](crate::GenericTlv), whose content type is fixed at compile time, a
+/// `TlvNode` decides at runtime whether the value of a record is itself a sequence of records. This
+/// is useful to inspect and log packets whose structure is unknown.
+///
+/// The [`Display`](fmt::Display) implementation prints the tree with one record per line, showing
+/// type, length and, for leaves, the value as hex and ASCII.
+///
+/// Unless the [`DecodeConfig`] sets a maximum depth, records are nested at most
+/// [`DEFAULT_MAX_NODE_DEPTH`] levels deep.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TlvNode {
+    /// A record whose value is kept as raw bytes
+    Leaf {
+        /// TLV-TYPE
+        typ: VarNum,
+        /// TLV-VALUE
+        value: Bytes,
+    },
+    /// A record whose value is a sequence of records
+    Nested {
+        /// TLV-TYPE
+        typ: VarNum,
+        /// The records contained in the value
+        children: Vec<TlvNode>,
+    },
+}
+
+impl TlvNode {
+    /// Parse a single record, treating values as nested whenever [`looks_nested`] returns true
+    pub fn parse(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode(bytes)
+    }
+
+    /// Parse a single record, treating values as nested whenever `is_container` returns true
+    ///
+    /// `is_container` is called with the type and value of each record. Unlike [`parse`], errors
+    /// while parsing the value of a container are returned rather than falling back to a leaf.
+    ///
+    /// [`parse`]: TlvNode::parse
+    pub fn parse_with(
+        bytes: &mut Bytes,
+        config: &DecodeConfig,
+        is_container: &dyn Fn(usize, &[u8]) -> bool,
+    ) -> Result<Self> {
+        Self::parse_inner(
+            bytes,
+            &limit_depth(config),
+            &|typ, value| Some(is_container(typ, value)).filter(|&c| c),
+            &mut None,
+        )
+    }
+
+    /// Parse all records in `bytes`, see [`parse`](TlvNode::parse)
+    pub fn parse_all(bytes: &mut Bytes) -> Result<Vec<Self>> {
+        let mut nodes = Vec::new();
+        let config = limit_depth(&DecodeConfig::default());
+        Self::parse_all_inner(bytes, &config, &heuristic, &mut nodes)?;
+        Ok(nodes)
+    }
+
+    /// Parse all records in `bytes`, see [`parse_with`](TlvNode::parse_with)
+    pub fn parse_all_with(
+        bytes: &mut Bytes,
+        config: &DecodeConfig,
+        is_container: &dyn Fn(usize, &[u8]) -> bool,
+    ) -> Result<Vec<Self>> {
+        let (nodes, result) = Self::parse_all_partial(bytes, config, is_container);
+        result.map(|()| nodes)
+    }
+
+    /// Parse all records in `bytes` like [`parse_all_with`], keeping the records parsed before an
+    /// error
+    ///
+    /// If the value of a container fails to parse, the container is returned as
+    /// [`TlvNode::Nested`] with the children parsed before the error, showing where parsing
+    /// stopped. Such a record does not encode to its original bytes.
+    ///
+    /// [`parse_all_with`]: TlvNode::parse_all_with
+    pub fn parse_all_partial(
+        bytes: &mut Bytes,
+        config: &DecodeConfig,
+        is_container: &dyn Fn(usize, &[u8]) -> bool,
+    ) -> (Vec<Self>, Result<()>) {
+        let mut nodes = Vec::new();
+        let result = Self::parse_all_inner(
+            bytes,
+            &limit_depth(config),
+            &|typ, value| Some(is_container(typ, value)).filter(|&c| c),
+            &mut nodes,
+        );
+        (nodes, result)
+    }
+
+    /// The TLV-TYPE of the record
+    pub fn typ(&self) -> VarNum {
+        match *self {
+            TlvNode::Leaf { typ, .. } | TlvNode::Nested { typ, .. } => typ,
+        }
+    }
+
+    /// The TLV-LENGTH of the record
+    pub fn len(&self) -> usize {
+        match self {
+            TlvNode::Leaf { value, .. } => value.len(),
+            TlvNode::Nested { children, .. } => children.size(),
+        }
+    }
+
+    /// Whether the TLV-VALUE of the record is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The records contained in the value, or an empty slice for a leaf
+    pub fn children(&self) -> &[TlvNode] {
+        match self {
+            TlvNode::Leaf { .. } => &[],
+            TlvNode::Nested { children, .. } => children,
+        }
+    }
+
+    /// `is_container` returns `None` for leaves, `Some(true)` for containers whose value must
+    /// parse and `Some(false)` for containers that fall back to a leaf if their value does not
+    ///
+    /// If the value of a container fails to parse, the container with the children parsed before
+    /// the error is stored in `partial`.
+    fn parse_inner(
+        bytes: &mut Bytes,
+        config: &DecodeConfig,
+        is_container: &dyn Fn(usize, &[u8]) -> Option<bool>,
+        partial: &mut Option<Self>,
+    ) -> Result<Self> {
+        decode_record(bytes, config, None, |typ, value| {
+            let Some(required) = is_container(typ.into(), &value) else {
+                return Ok(TlvNode::Leaf { typ, value });
+            };
+            let mut children = Vec::new();
+            let result = config.nested().and_then(|config| {
+                Self::parse_all_inner(&mut value.clone(), &config, is_container, &mut children)
+            });
+            match result {
+                Ok(()) => Ok(TlvNode::Nested { typ, children }),
+                Err(_) if !required => Ok(TlvNode::Leaf { typ, value }),
+                Err(e) => {
+                    *partial = Some(TlvNode::Nested { typ, children });
+                    Err(e.in_tlv(None, typ.into()))
+                }
+            }
+        })
+    }
+
+    /// Parses records into `nodes` until `bytes` is empty or an error occurs
+    fn parse_all_inner(
+        bytes: &mut Bytes,
+        config: &DecodeConfig,
+        is_container: &dyn Fn(usize, &[u8]) -> Option<bool>,
+        nodes: &mut Vec<Self>,
+    ) -> Result<()> {
+        let len = bytes.len();
+        while bytes.has_remaining() {
+            let remaining = bytes.remaining();
+            config
+                .check_elements(nodes.len() + 1)
+                .map_err(|e| e.at(len - remaining).relative_to(len))?;
+            let mut partial = None;
+            match Self::parse_inner(bytes, config, is_container, &mut partial) {
+                Ok(node) => nodes.push(node),
+                Err(e) => {
+                    nodes.extend(partial);
+                    return Err(e.within(len - remaining, remaining).relative_to(len));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{} [{}]",
+            "",
+            self.typ(),
+            self.len(),
+            indent = depth * 2
+        )?;
+        match self {
+            TlvNode::Leaf { value, .. } if !value.is_empty() => {
+                write!(f, " {:#}", HexBytes(value))?;
+            }
+            TlvNode::Leaf { .. } => {}
+            TlvNode::Nested { children, .. } => {
+                for child in children {
+                    f.write_str("\n")?;
+                    child.fmt_indented(f, depth + 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats bytes as lowercase hex digits
+///
+/// The alternate form (`{:#}`) is followed by the bytes as ASCII in quotes, with non-printable
+/// characters replaced by `.`. It is used for leaves in the `Display` output of [`TlvNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexBytes<'a>(pub &'a [u8]);
+
+impl fmt::Display for HexBytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        if f.alternate() {
+            f.write_str(" \"")?;
+            for &byte in self.0 {
+                let c = if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+            f.write_str("\"")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns whether `value` appears to consist of TLV records
+///
+/// This is the case if `value` is not empty and can be split into complete TLV records without
+/// any bytes left over. Short printable strings frequently satisfy this by chance, so the result
+/// should only be used for display purposes.
+pub fn looks_nested(value: &[u8]) -> bool {
+    let mut cur = value;
+    while !cur.is_empty() {
+        if VarNum::decode_ref(&mut cur).is_err() {
+            return false;
+        }
+        match VarNum::decode_ref(&mut cur) {
+            Ok(len) if cur.len() >= usize::from(len) => cur.advance(len.into()),
+            _ => return false,
+        }
+    }
+    !value.is_empty()
+}
+
+fn limit_depth(config: &DecodeConfig) -> DecodeConfig {
+    match config.limits().max_depth() {
+        Some(_) => *config,
+        None => config.with_limits(config.limits().with_max_depth(DEFAULT_MAX_NODE_DEPTH)),
+    }
+}
+
+fn heuristic(_typ: usize, value: &[u8]) -> Option<bool> {
+    Some(false).filter(|_| looks_nested(value))
+}
+
+impl TlvEncode for TlvNode {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        self.typ().encode_to(buf);
+        VarNum::from(self.len()).encode_to(buf);
+        match self {
+            TlvNode::Leaf { value, .. } => value.encode_to(buf),
+            TlvNode::Nested { children, .. } => children.encode_to(buf),
+        }
+    }
+
+    fn size(&self) -> usize {
+        let len = self.len();
+        self.typ().size() + VarNum::from(len).size() + len
+    }
+}
+
+/// Decodes a single record, treating values as nested whenever [`looks_nested`] returns true
+impl TlvDecode for TlvNode {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        Self::parse_inner(bytes, &limit_depth(config), &heuristic, &mut None)
+    }
+}
+
+impl fmt::Display for TlvNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::ToString;
+
+    use crate::{DecodeLimits, TlvError};
+
+    use super::*;
+
+    const DATA: &[u8] = &[6, 13, 7, 6, 8, 1, b'a', 8, 1, b'b', 21, 3, b'h', b'i', 0];
+
+    #[test]
+    fn heuristic_parse() {
+        let mut data = Bytes::from_static(DATA);
+        let node = TlvNode::parse(&mut data).unwrap();
+        assert!(data.is_empty());
+        assert_eq!(node.typ().value(), 6);
+        assert_eq!(node.len(), 13);
+        assert_eq!(node.children().len(), 2);
+        assert_eq!(node.children()[0].children().len(), 2);
+        assert_eq!(
+            node.children()[1],
+            TlvNode::Leaf {
+                typ: VarNum::new(21),
+                value: Bytes::from_static(b"hi\0"),
+            }
+        );
+        assert_eq!(node.encode(), DATA);
+    }
+
+    #[test]
+    fn container_rules() {
+        let mut data = Bytes::from_static(DATA);
+        let node =
+            TlvNode::parse_with(&mut data, &DecodeConfig::default(), &|typ, _| typ == 6).unwrap();
+        assert_eq!(node.children().len(), 2);
+        assert!(matches!(node.children()[0], TlvNode::Leaf { .. }));
+        assert_eq!(node.encode(), DATA);
+
+        // Containers whose value is not valid are an error
+        let mut data = Bytes::from_static(&[6, 2, 7, 5]);
+        let error =
+            TlvNode::parse_with(&mut data, &DecodeConfig::default(), &|_, _| true).unwrap_err();
+        assert_eq!(error, TlvError::UnexpectedEndOfStream);
+        assert_eq!(error.context().unwrap().offset, Some(3));
+    }
+
+    #[test]
+    fn not_nested() {
+        assert!(!looks_nested(b""));
+        assert!(!looks_nested(&[8, 5, b'a']));
+        assert!(looks_nested(&[8, 1, b'a', 9, 0]));
+
+        let node = TlvNode::parse(&mut Bytes::from_static(&[8, 3, 8, 5, b'a'])).unwrap();
+        assert!(matches!(node, TlvNode::Leaf { .. }));
+    }
+
+    #[test]
+    fn parse_all() {
+        let mut data = Bytes::from_static(&[8, 1, b'a', 9, 0]);
+        let nodes = TlvNode::parse_all(&mut data).unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes[1].is_empty());
+        assert_eq!(
+            TlvNode::parse_all(&mut Bytes::from_static(&[8, 2, b'a'])).unwrap_err(),
+            TlvError::UnexpectedEndOfStream
+        );
+    }
+
+    #[test]
+    fn partial() {
+        let mut data = Bytes::from_static(&[9, 0, 7, 6, 8, 1, b'a', 8, 5, b'b']);
+        let (nodes, result) =
+            TlvNode::parse_all_partial(&mut data, &DecodeConfig::default(), &|typ, _| typ == 7);
+        let error = result.unwrap_err();
+        assert_eq!(error, TlvError::UnexpectedEndOfStream);
+        assert_eq!(error.context().unwrap().offset, Some(8));
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[1].children().len(), 1);
+        assert_eq!(nodes[1].children()[0].encode(), &[8, 1, b'a'][..]);
+    }
+
+    #[test]
+    fn depth_limit() {
+        let config = DecodeConfig::new().with_limits(DecodeLimits::new().with_max_depth(1));
+        let mut data = Bytes::from_static(DATA);
+        let node = TlvNode::decode_with(&mut data, &config).unwrap();
+        assert!(matches!(node.children()[0], TlvNode::Leaf { .. }));
+
+        let error = TlvNode::parse_with(&mut Bytes::from_static(DATA), &config, &|typ, _| {
+            typ != 8 && typ != 21
+        })
+        .unwrap_err();
+        assert_eq!(error, TlvError::DepthLimitExceeded { max: 1 });
+    }
+
+    #[test]
+    fn default_depth_limit() {
+        let mut wire = alloc::vec![8, 0];
+        for _ in 0..DEFAULT_MAX_NODE_DEPTH + 8 {
+            wire.splice(0..0, [7, wire.len() as u8]);
+        }
+        let wire = Bytes::from(wire);
+
+        // The heuristic falls back to a leaf below the maximum depth
+        let mut node = TlvNode::parse(&mut wire.clone()).unwrap();
+        let mut depth = 0;
+        while let [child] = node.children() {
+            node = child.clone();
+            depth += 1;
+        }
+        assert_eq!(depth, DEFAULT_MAX_NODE_DEPTH);
+        assert!(matches!(node, TlvNode::Leaf { .. }));
+
+        let error = TlvNode::parse_with(&mut wire.clone(), &DecodeConfig::default(), &|typ, _| {
+            typ == 7
+        })
+        .unwrap_err();
+        assert_eq!(
+            error,
+            TlvError::DepthLimitExceeded {
+                max: DEFAULT_MAX_NODE_DEPTH
+            }
+        );
+
+        // An explicit limit replaces the default
+        let config = DecodeConfig::new().with_limits(DecodeLimits::new().with_max_depth(64));
+        let node = TlvNode::parse_with(&mut wire.clone(), &config, &|typ, _| typ == 7).unwrap();
+        assert_eq!(node.encode(), wire);
+    }
+
+    #[test]
+    fn hex_bytes() {
+        assert_eq!(HexBytes(b"a\x00 ").to_string(), "610020");
+        assert_eq!(format!("{:#}", HexBytes(b"a\x00 ")), "610020 \"a. \"");
+        assert_eq!(format!("{:#}", HexBytes(b"")), " \"\"");
+    }
+
+    #[test]
+    fn display() {
+        let node = TlvNode::parse(&mut Bytes::from_static(DATA)).unwrap();
+        let expected = [
+            "6 [13]",
+            "  7 [6]",
+            "    8 [1] 61 \"a\"",
+            "    8 [1] 62 \"b\"",
+            "  21 [3] 686900 \"hi.\"",
+        ];
+        assert_eq!(node.to_string(), expected.join("\n"));
+    }
+}
@@ -0,0 +1,141 @@
+use bytes::{Buf, Bytes};
+
+use crate::tlv::split_record;
+use crate::{tlv_typ_critical, DecodeConfig, Result, TlvError, UnknownTlvs};
+
+/// Decode the TLV records in `bytes`, which must appear in the order given by `order`
+///
+/// This is used for records such as packets, whose sub-TLVs must appear in a fixed order but can
+/// be interleaved with unknown records. `order` lists the TLV-TYPEs of the known records. For each
+/// known record, `field` is called with the TLV-TYPE and the entire record, including type and
+/// length. Each known record may appear at most once.
+///
+/// Known records that appear after a record that should follow them lead to
+/// [`TlvError::OutOfOrder`], and repeated records to [`TlvError::DuplicateField`]. Unknown
+/// critical records lead to [`TlvError::UnknownCritical`]. Unknown non-critical records are added
+/// to `extra` if given, and skipped otherwise. Their position is the index in `order` of the record
+/// that may follow them, so that calling [`UnknownTlvs::encode_at`] with each index before
+/// encoding the matching record, and with `order.len()` at the end, restores them.
+///
+/// Errors returned by `field` are given the position of the record and, unless it is already
+/// there, the record itself in their [`ErrorContext`](crate::ErrorContext), named as in
+/// [`ndn_type`](crate::ndn_type). Use [`required_field`](crate::required_field) to detect missing
+/// records. All of `bytes` is consumed.
+pub fn decode_ordered(
+    bytes: &mut Bytes,
+    config: &DecodeConfig,
+    order: &[usize],
+    mut extra: Option<&mut UnknownTlvs>,
+    mut field: impl FnMut(usize, &mut Bytes) -> Result<()>,
+) -> Result<()> {
+    let len = bytes.len();
+    let mut last = None;
+    while bytes.has_remaining() {
+        let remaining = bytes.len();
+        let mut decode_record = || {
+            let mut record = bytes.clone();
+            let (typ, _) = split_record(bytes, config)?;
+            record.truncate(record.len() - bytes.len());
+            let (typ, record_len) = (usize::from(typ), record.len());
+
+            let Some(position) = order.iter().position(|&t| t == typ) else {
+                if tlv_typ_critical(typ) {
+                    return Err(TlvError::UnknownCritical { typ });
+                }
+                if let Some(extra) = extra.as_deref_mut() {
+                    extra.push_record(last.map_or(0, |last| last + 1), typ, record);
+                }
+                return Ok(());
+            };
+            match last {
+                Some(last) if last == position => return Err(TlvError::DuplicateField { typ }),
+                Some(last) if last > position => return Err(TlvError::OutOfOrder { typ }),
+                _ => last = Some(position),
+            }
+            field(typ, &mut record).map_err(|e| e.within(0, record_len).in_record(typ))
+        };
+        decode_record().map_err(|e| e.within(len - remaining, remaining).relative_to(len))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn types(data: &'static [u8]) -> Result<Vec<usize>> {
+        let mut found = Vec::new();
+        decode_ordered(
+            &mut Bytes::from_static(data),
+            &DecodeConfig::default(),
+            &[7, 33, 10],
+            None,
+            |typ, record| {
+                assert_eq!(record[0] as usize, typ);
+                found.push(typ);
+                Ok(())
+            },
+        )?;
+        Ok(found)
+    }
+
+    #[test]
+    fn ordered() {
+        assert_eq!(types(&[7, 0, 33, 0, 10, 1, 0]).unwrap(), [7, 33, 10]);
+        assert_eq!(types(&[7, 0, 10, 1, 0]).unwrap(), [7, 10]);
+        assert_eq!(types(&[200, 0, 7, 0, 202, 1, 0]).unwrap(), [7]);
+        assert!(types(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let error = types(&[7, 0, 10, 1, 0, 33, 0]).unwrap_err();
+        assert_eq!(error, TlvError::OutOfOrder { typ: 33 });
+        assert_eq!(error.context().unwrap().offset, Some(5));
+
+        assert_eq!(
+            types(&[7, 0, 7, 0]).unwrap_err(),
+            TlvError::DuplicateField { typ: 7 }
+        );
+        assert_eq!(
+            types(&[7, 0, 201, 0]).unwrap_err(),
+            TlvError::UnknownCritical { typ: 201 }
+        );
+        assert_eq!(
+            types(&[7, 0, 33, 2, 0]).unwrap_err(),
+            TlvError::UnexpectedEndOfStream
+        );
+    }
+
+    #[test]
+    fn extra() {
+        let data = [200, 0, 7, 0, 202, 1, 1, 204, 0, 10, 1, 0, 206, 0];
+        let mut extra = UnknownTlvs::new();
+        let mut known = Vec::new();
+        decode_ordered(
+            &mut Bytes::copy_from_slice(&data),
+            &DecodeConfig::default(),
+            &[7, 33, 10],
+            Some(&mut extra),
+            |_, record| {
+                known.push(record.clone());
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        let positions: Vec<_> = extra.iter().map(|tlv| tlv.position).collect();
+        assert_eq!(positions, [0, 1, 1, 3]);
+        let mut encoded = Vec::new();
+        for (position, typ) in [7, 33, 10].into_iter().enumerate() {
+            extra.encode_at(position, &mut encoded);
+            if let Some(record) = known.iter().find(|record| record[0] == typ) {
+                encoded.extend_from_slice(record);
+            }
+        }
+        extra.encode_at(3, &mut encoded);
+        assert_eq!(encoded, data);
+    }
+}
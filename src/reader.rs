@@ -0,0 +1,352 @@
+use std::io::{ErrorKind, Read};
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::tlv::check_type;
+use crate::{Result, Tlv, TlvDecode, TlvDecodeRef, TlvError, VarNum};
+
+/// Default upper bound for the size of a single TLV record
+///
+/// This is the `MAX_NDN_PACKET_SIZE` used by most NDN implementations.
+pub const DEFAULT_MAX_RECORD_SIZE: usize = 8800;
+
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// Reads successive TLV records from a type implementing `Read`
+///
+/// Data is read in chunks, and bytes read past the end of one record are kept for the next one.
+/// This makes `TlvReader` suitable for streams of back-to-back records, such as NDN packets
+/// arriving on a socket or stored in a file.
+///
+/// Records larger than the configured maximum are rejected with [`TlvError::RecordTooLarge`]
+/// before any of their content is buffered. After such an error, or any other decoding error, the
+/// stream position is unknown and the reader should be discarded.
+#[derive(Debug)]
+pub struct TlvReader<R> {
+    reader: R,
+    buf: BytesMut,
+    max_record_size: usize,
+}
+
+impl<R: Read> TlvReader<R> {
+    /// Create a new `TlvReader` using [`DEFAULT_MAX_RECORD_SIZE`]
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: BytesMut::new(),
+            max_record_size: DEFAULT_MAX_RECORD_SIZE,
+        }
+    }
+
+    /// Set the maximum size of a single record, including type and length
+    pub fn with_max_record_size(mut self, max_record_size: usize) -> Self {
+        self.max_record_size = max_record_size;
+        self
+    }
+
+    /// The maximum size of a single record, including type and length
+    pub fn max_record_size(&self) -> usize {
+        self.max_record_size
+    }
+
+    /// Read the next complete TLV record, including its type and length
+    ///
+    /// Returns `Ok(None)` if the underlying reader reached end of file between two records. End
+    /// of file within a record results in [`TlvError::UnexpectedEndOfStream`].
+    pub fn next_record(&mut self) -> Result<Option<Bytes>> {
+        let Some((_, record_len)) = self.fill_header()? else {
+            return Ok(None);
+        };
+        self.fill_to(record_len)?;
+        Ok(Some(self.buf.split_to(record_len).freeze()))
+    }
+
+    /// Read and decode the next TLV record as `T`
+    ///
+    /// Returns `Ok(None)` if the underlying reader reached end of file between two records. If the
+    /// next record is of a different type, [`TlvError::TypeMismatch`] is returned and the record
+    /// is left in the buffer, so it can still be read using [`next_record`](Self::next_record).
+    pub fn next_tlv<T: Tlv + TlvDecode>(&mut self) -> Result<Option<T>> {
+        let Some((typ, record_len)) = self.fill_header()? else {
+            return Ok(None);
+        };
+        check_type(typ, T::TYP)?;
+        self.fill_to(record_len)?;
+        T::decode(&mut self.buf.split_to(record_len).freeze()).map(Some)
+    }
+
+    /// Bytes that have been read from the underlying reader, but not yet returned
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Get a reference to the underlying reader
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader
+    ///
+    /// Reading directly from the underlying reader will corrupt the stream of records.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Unwrap this `TlvReader`, returning the underlying reader
+    ///
+    /// Any buffered data is lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Make sure the type and length of the next record are buffered
+    ///
+    /// Returns the type and total length of the record, or `None` on a clean end of file
+    fn fill_header(&mut self) -> Result<Option<(usize, usize)>> {
+        loop {
+            if let Some(header) = record_header(&self.buf, self.max_record_size)? {
+                return Ok(Some(header));
+            }
+            if self.read_chunk()? == 0 {
+                return if self.buf.is_empty() {
+                    Ok(None)
+                } else {
+                    Err(TlvError::UnexpectedEndOfStream)
+                };
+            }
+        }
+    }
+
+    /// Make sure at least `len` bytes are buffered
+    fn fill_to(&mut self, len: usize) -> Result<()> {
+        self.buf.reserve(len.saturating_sub(self.buf.len()));
+        while self.buf.len() < len {
+            if self.read_chunk()? == 0 {
+                return Err(TlvError::UnexpectedEndOfStream);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_chunk(&mut self) -> Result<usize> {
+        let start = self.buf.len();
+        self.buf.resize(start + READ_CHUNK_SIZE, 0);
+        loop {
+            match self.reader.read(&mut self.buf[start..]) {
+                Ok(bytes_read) => {
+                    self.buf.truncate(start + bytes_read);
+                    return Ok(bytes_read);
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.buf.truncate(start);
+                    return Err(TlvError::IOError(e));
+                }
+            }
+        }
+    }
+}
+
+/// Parse type and total length of the record at the start of `buf`
+///
+/// Returns `None` if `buf` does not yet contain the entire header.
+pub(crate) fn record_header(buf: &[u8], max_record_size: usize) -> Result<Option<(usize, usize)>> {
+    let mut cur = buf;
+    let (typ, len) = match (VarNum::decode_ref(&mut cur), VarNum::decode_ref(&mut cur)) {
+        (Ok(typ), Ok(len)) => (typ, len),
+        (Err(TlvError::UnexpectedEndOfStream), _) | (_, Err(TlvError::UnexpectedEndOfStream)) => {
+            return Ok(None)
+        }
+        (Err(e), _) | (_, Err(e)) => return Err(e),
+    };
+    let header_len = buf.len() - cur.len();
+    let record_len = usize::try_from(len.value())
+        .ok()
+        .and_then(|len| len.checked_add(header_len))
+        .filter(|&record_len| record_len <= max_record_size)
+        .ok_or(TlvError::RecordTooLarge {
+            max: max_record_size,
+        })?;
+    Ok(Some((typ.into(), record_len)))
+}
+
+/// Grow `bytes`, which holds a complete record header, to the total length of the record
+///
+/// Returns the length of the header, at which the value of the record starts.
+pub(crate) fn extend_to_record(bytes: &mut BytesMut, max_record_size: usize) -> Result<usize> {
+    let (_, record_len) =
+        record_header(bytes, max_record_size)?.expect("Header has been read completely");
+    let header_len = bytes.len();
+    bytes.resize(record_len, 0);
+    Ok(header_len)
+}
+
+/// Convert an error from `read_exact`, treating end of file as the end of the TLV stream
+pub(crate) fn read_error(e: std::io::Error) -> TlvError {
+    match e.kind() {
+        ErrorKind::UnexpectedEof => TlvError::UnexpectedEndOfStream,
+        _ => TlvError::IOError(e),
+    }
+}
+
+/// Read exactly one TLV record from `reader`, without reading past its end
+///
+/// If `expected_typ` is given, reading stops with an error as soon as the type is known to differ.
+pub(crate) fn read_record(
+    mut reader: impl Read,
+    expected_typ: Option<usize>,
+    max_record_size: usize,
+) -> Result<Bytes> {
+    let mut bytes = BytesMut::new();
+
+    let typ = read_varnum(&mut reader, &mut bytes)?;
+    if let Some(expected) = expected_typ {
+        check_type(typ.into(), expected)?;
+    }
+    read_varnum(&mut reader, &mut bytes)?;
+
+    let header_len = extend_to_record(&mut bytes, max_record_size)?;
+    read_exact(&mut reader, &mut bytes[header_len..])?;
+    Ok(bytes.freeze())
+}
+
+/// Read a single `VarNum` from `reader`, appending its encoded form to `bytes`
+fn read_varnum(reader: &mut impl Read, bytes: &mut BytesMut) -> Result<VarNum> {
+    let mut buf = [0; 9];
+    read_exact(reader, &mut buf[..1])?;
+    let len = VarNum::encoded_size(buf[0]);
+    read_exact(reader, &mut buf[1..len])?;
+    bytes.put(&buf[..len]);
+    VarNum::decode_ref(&mut &buf[..len])
+}
+
+fn read_exact(reader: &mut impl Read, buf: &mut [u8]) -> Result<()> {
+    reader.read_exact(buf).map_err(read_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::tests::GenericNameComponent;
+
+    use super::*;
+
+    /// Returns at most one byte per call to `read`
+    struct Trickle<R>(R);
+
+    impl<R: Read> Read for Trickle<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(1);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    const RECORDS: [u8; 15] = [
+        8, 5, b'h', b'e', b'l', b'l', b'o', 33, 0, 8, 4, b'a', b'b', b'c', b'd',
+    ];
+
+    #[test]
+    fn back_to_back() {
+        let mut reader = TlvReader::new(Cursor::new(&RECORDS[..]));
+
+        assert_eq!(reader.next_record().unwrap().unwrap(), &RECORDS[0..7]);
+        assert_eq!(reader.next_record().unwrap().unwrap(), &RECORDS[7..9]);
+        assert_eq!(reader.next_record().unwrap().unwrap(), &RECORDS[9..]);
+        assert_eq!(reader.next_record().unwrap(), None);
+    }
+
+    #[test]
+    fn short_reads() {
+        let mut reader = TlvReader::new(Trickle(Cursor::new(&RECORDS[..])));
+
+        let component: GenericNameComponent = reader.next_tlv().unwrap().unwrap();
+        assert_eq!(component.name, &b"hello"[..]);
+        assert_eq!(reader.next_record().unwrap().unwrap(), &RECORDS[7..9]);
+        let component: GenericNameComponent = reader.next_tlv().unwrap().unwrap();
+        assert_eq!(component.name, &b"abcd"[..]);
+        assert!(reader.next_tlv::<GenericNameComponent>().unwrap().is_none());
+    }
+
+    #[test]
+    fn type_mismatch_keeps_record() {
+        let mut reader = TlvReader::new(Cursor::new(&RECORDS[7..]));
+
+        assert_eq!(
+            reader.next_tlv::<GenericNameComponent>().unwrap_err(),
+            TlvError::TypeMismatch {
+                expected: 8,
+                found: 33
+            }
+        );
+        assert_eq!(reader.next_record().unwrap().unwrap(), &RECORDS[7..9]);
+    }
+
+    #[test]
+    fn eof_within_record() {
+        let mut reader = TlvReader::new(Cursor::new(&RECORDS[..12]));
+
+        reader.next_record().unwrap();
+        reader.next_record().unwrap();
+        assert_eq!(
+            reader.next_record().unwrap_err(),
+            TlvError::UnexpectedEndOfStream
+        );
+    }
+
+    #[test]
+    fn eof_within_header() {
+        let mut reader = TlvReader::new(Cursor::new(&[0xFD, 0x01][..]));
+        assert_eq!(
+            reader.next_record().unwrap_err(),
+            TlvError::UnexpectedEndOfStream
+        );
+    }
+
+    #[test]
+    fn record_too_large() {
+        let mut reader = TlvReader::new(Cursor::new(&RECORDS[..])).with_max_record_size(6);
+        assert_eq!(
+            reader.next_record().unwrap_err(),
+            TlvError::RecordTooLarge { max: 6 }
+        );
+
+        let huge = [8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        let mut reader = TlvReader::new(Cursor::new(&huge[..]));
+        assert_eq!(
+            reader.next_record().unwrap_err(),
+            TlvError::RecordTooLarge {
+                max: DEFAULT_MAX_RECORD_SIZE
+            }
+        );
+    }
+
+    #[test]
+    fn large_record() {
+        let mut data = vec![8, 0xFD, 0x07, 0xD0];
+        data.extend((0..2000).map(|i| i as u8));
+        data.extend_from_slice(&RECORDS[7..9]);
+        let mut reader = TlvReader::new(Cursor::new(&data));
+
+        let component: GenericNameComponent = reader.next_tlv().unwrap().unwrap();
+        assert_eq!(component.name, &data[4..2004]);
+        assert_eq!(reader.buffer(), &RECORDS[7..9]);
+    }
+
+    #[test]
+    fn read_record_exact() {
+        let mut cursor = Cursor::new(&RECORDS[..]);
+
+        let record = read_record(&mut cursor, Some(8), DEFAULT_MAX_RECORD_SIZE).unwrap();
+        assert_eq!(record, &RECORDS[0..7]);
+        assert_eq!(cursor.position(), 7);
+
+        assert_eq!(
+            read_record(&mut cursor, Some(8), DEFAULT_MAX_RECORD_SIZE).unwrap_err(),
+            TlvError::TypeMismatch {
+                expected: 8,
+                found: 33
+            }
+        );
+    }
+}
@@ -0,0 +1,774 @@
+use alloc::vec::Vec;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::ser::{self, Serialize};
+
+use crate::{NonNegativeInteger, Result, TlvEncode, TlvError, VarNum};
+
+/// Serialize `value` in TLV format
+///
+/// This allows types implementing [`Serialize`] to be encoded without implementing [`TlvEncode`].
+/// Values are mapped as follows:
+///
+/// - Each field of a struct becomes a TLV record. The TLV-TYPE is taken from the field name,
+///   which is either a number, as in `#[serde(rename = "7")]`, or ends in `_` and a number, as in
+///   `name_7`. The top-level struct is encoded as the concatenation of its fields.
+/// - Integers and `bool` are encoded as [`NonNegativeInteger`]s. Negative numbers are an error.
+/// - Floating point numbers are encoded as 4 or 8 bytes in network byte order.
+/// - Strings and byte arrays are encoded as raw values. Note that `Vec<u8>` is a sequence, use a
+///   wrapper such as `serde_bytes` to encode it as a byte array.
+/// - A field containing `None` is omitted, a field containing a sequence is repeated for each
+///   element.
+/// - Maps are encoded like structs, with integer keys as TLV-TYPEs.
+/// - An enum variant is encoded as a record whose TLV-TYPE is taken from the variant name, like
+///   struct fields. Its value is empty for unit variants.
+/// - Tuples and tuple variants are not supported.
+///
+/// Use [`from_bytes`](crate::from_bytes) to deserialize the result.
+pub fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Result<Bytes> {
+    let mut out = BytesMut::new();
+    value.serialize(ValueSerializer { out: &mut out })?;
+    Ok(out.freeze())
+}
+
+/// Get the TLV-TYPE from a struct field or enum variant name
+pub(crate) fn type_number(name: &str) -> Result<usize> {
+    let number = name.rsplit_once('_').map_or(name, |(_, number)| number);
+    if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(TlvError::custom(format_args!(
+            "`{name}` does not specify a TLV type number"
+        )));
+    }
+    number
+        .parse()
+        .map_err(|_| TlvError::custom(format_args!("invalid TLV type number in `{name}`")))
+}
+
+fn put_record(out: &mut BytesMut, typ: usize, value: &[u8]) {
+    VarNum::from(typ).encode_to(out);
+    VarNum::from(value.len()).encode_to(out);
+    out.put_slice(value);
+}
+
+fn unsupported(what: &str) -> TlvError {
+    TlvError::custom(format_args!("{what} cannot be serialized in TLV format"))
+}
+
+fn non_negative(value: i64) -> Result<u64> {
+    u64::try_from(value).map_err(|_| TlvError::custom("negative integers cannot be encoded"))
+}
+
+/// Serializes a value as the TLV-VALUE of a record
+struct ValueSerializer<'a> {
+    out: &'a mut BytesMut,
+}
+
+/// Serializes a struct field as zero or more records of type `typ`
+struct FieldSerializer<'a> {
+    typ: usize,
+    out: &'a mut BytesMut,
+}
+
+/// Serializes the records of a struct, map or struct variant
+///
+/// The records are written to `buf` and then wrapped in a record for each of `headers`, innermost
+/// last, before being written to `out`.
+struct Compound<'a> {
+    out: &'a mut BytesMut,
+    headers: Vec<usize>,
+    buf: BytesMut,
+    key: Option<usize>,
+}
+
+/// Serializes the elements of a sequence as records of type `typ`
+struct SeqSerializer<'a> {
+    typ: usize,
+    out: &'a mut BytesMut,
+}
+
+impl<'a> Compound<'a> {
+    fn new(out: &'a mut BytesMut, headers: Vec<usize>) -> Self {
+        Self {
+            out,
+            headers,
+            buf: BytesMut::new(),
+            key: None,
+        }
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, typ: usize, value: &T) -> Result<()> {
+        value.serialize(FieldSerializer {
+            typ,
+            out: &mut self.buf,
+        })
+    }
+
+    fn finish(self) -> Result<()> {
+        let mut value = self.buf;
+        for &typ in self.headers.iter().rev() {
+            let mut record = BytesMut::new();
+            put_record(&mut record, typ, &value);
+            value = record;
+        }
+        self.out.put_slice(&value);
+        Ok(())
+    }
+}
+
+impl<'a> FieldSerializer<'a> {
+    /// Serialize a value that is encoded as a single record
+    fn record(self, f: impl FnOnce(ValueSerializer<'_>) -> Result<()>) -> Result<()> {
+        let mut value = BytesMut::new();
+        f(ValueSerializer { out: &mut value })?;
+        put_record(self.out, self.typ, &value);
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for ValueSerializer<'a> {
+    type Ok = ();
+    type Error = TlvError;
+    type SerializeSeq = ser::Impossible<(), TlvError>;
+    type SerializeTuple = ser::Impossible<(), TlvError>;
+    type SerializeTupleStruct = ser::Impossible<(), TlvError>;
+    type SerializeTupleVariant = ser::Impossible<(), TlvError>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.serialize_u64(non_negative(v)?)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        NonNegativeInteger::new(v).encode_to(self.out);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.out.put_f32(v);
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.out.put_f64(v);
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.out.put_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        put_record(self.out, type_number(variant)?, &[]);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        let typ = type_number(variant)?;
+        FieldSerializer { typ, out: self.out }.record(|s| value.serialize(s))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(TlvError::custom(
+            "sequences can only be serialized as struct fields",
+        ))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(unsupported("tuples"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported("tuple structs"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported("tuple variants"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(Compound::new(self.out, Vec::new()))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Ok(Compound::new(self.out, Vec::new()))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Ok(Compound::new(self.out, alloc::vec![type_number(variant)?]))
+    }
+}
+
+impl<'a> ser::Serializer for FieldSerializer<'a> {
+    type Ok = ();
+    type Error = TlvError;
+    type SerializeSeq = SeqSerializer<'a>;
+    type SerializeTuple = ser::Impossible<(), TlvError>;
+    type SerializeTupleStruct = ser::Impossible<(), TlvError>;
+    type SerializeTupleVariant = ser::Impossible<(), TlvError>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.record(|s| s.serialize_bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.record(|s| s.serialize_i8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.record(|s| s.serialize_i16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.record(|s| s.serialize_i32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.record(|s| s.serialize_i64(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.record(|s| s.serialize_u8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.record(|s| s.serialize_u16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.record(|s| s.serialize_u32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.record(|s| s.serialize_u64(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.record(|s| s.serialize_f32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.record(|s| s.serialize_f64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.record(|s| s.serialize_char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.record(|s| s.serialize_str(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.record(|s| s.serialize_bytes(v))
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.record(|s| s.serialize_unit())
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<()> {
+        self.record(|s| s.serialize_unit_struct(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.record(|s| s.serialize_unit_variant(name, variant_index, variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.record(|s| s.serialize_newtype_variant(name, variant_index, variant, value))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(SeqSerializer {
+            typ: self.typ,
+            out: self.out,
+        })
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(unsupported("tuples"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported("tuple structs"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported("tuple variants"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(Compound::new(self.out, alloc::vec![self.typ]))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Ok(Compound::new(self.out, alloc::vec![self.typ]))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Ok(Compound::new(
+            self.out,
+            alloc::vec![self.typ, type_number(variant)?],
+        ))
+    }
+}
+
+impl<'a> ser::SerializeSeq for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = TlvError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        value.serialize(FieldSerializer {
+            typ: self.typ,
+            out: self.out,
+        })
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStruct for Compound<'a> {
+    type Ok = ();
+    type Error = TlvError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.field(type_number(key)?, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for Compound<'a> {
+    type Ok = ();
+    type Error = TlvError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.field(type_number(key)?, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeMap for Compound<'a> {
+    type Ok = ();
+    type Error = TlvError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let typ = self
+            .key
+            .take()
+            .ok_or_else(|| TlvError::custom("map value without key"))?;
+        self.field(typ, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+/// Serializes a map key as a TLV-TYPE
+struct KeySerializer;
+
+fn invalid_key() -> TlvError {
+    TlvError::custom("map keys must be TLV type numbers")
+}
+
+macro_rules! invalid_keys {
+    ($($method:ident($($arg:ty),*);)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<usize> {
+                Err(invalid_key())
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = usize;
+    type Error = TlvError;
+    type SerializeSeq = ser::Impossible<usize, TlvError>;
+    type SerializeTuple = ser::Impossible<usize, TlvError>;
+    type SerializeTupleStruct = ser::Impossible<usize, TlvError>;
+    type SerializeTupleVariant = ser::Impossible<usize, TlvError>;
+    type SerializeMap = ser::Impossible<usize, TlvError>;
+    type SerializeStruct = ser::Impossible<usize, TlvError>;
+    type SerializeStructVariant = ser::Impossible<usize, TlvError>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<usize> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<usize> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<usize> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<usize> {
+        self.serialize_u64(non_negative(v)?)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<usize> {
+        Ok(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<usize> {
+        Ok(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<usize> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<usize> {
+        usize::try_from(v).map_err(|_| invalid_key())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<usize> {
+        type_number(v)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<usize> {
+        value.serialize(self)
+    }
+
+    invalid_keys! {
+        serialize_bool(bool);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<usize> {
+        Err(invalid_key())
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<usize> {
+        Err(invalid_key())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(invalid_key())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(invalid_key())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(invalid_key())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(invalid_key())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(invalid_key())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(invalid_key())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(invalid_key())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+    use alloc::string::String;
+    use alloc::vec;
+
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Record {
+        #[serde(rename = "7")]
+        name: Vec<String>,
+        count_200: u32,
+        flag_202: Option<bool>,
+        inner_204: Inner,
+    }
+
+    #[derive(Serialize)]
+    struct Inner {
+        #[serde(rename = "206")]
+        value: u64,
+        kind_208: Kind,
+    }
+
+    #[derive(Serialize)]
+    enum Kind {
+        #[serde(rename = "1")]
+        Empty,
+        #[serde(rename = "3")]
+        Text(String),
+    }
+
+    #[test]
+    fn field_type_numbers() {
+        assert_eq!(type_number("7").unwrap(), 7);
+        assert_eq!(type_number("name_7").unwrap(), 7);
+        assert_eq!(type_number("a_b_300").unwrap(), 300);
+        assert!(type_number("name").is_err());
+        assert!(type_number("name_").is_err());
+        assert!(type_number("name_x7").is_err());
+    }
+
+    #[test]
+    fn record() {
+        let record = Record {
+            name: vec!["a".into(), "bc".into()],
+            count_200: 0x100,
+            flag_202: None,
+            inner_204: Inner {
+                value: 1,
+                kind_208: Kind::Text("x".into()),
+            },
+        };
+        assert_eq!(
+            to_bytes(&record).unwrap(),
+            &[7, 1, b'a', 7, 2, b'b', b'c', 200, 2, 1, 0, 204, 8, 206, 1, 1, 208, 3, 3, 1, b'x'][..]
+        );
+    }
+
+    #[test]
+    fn unit_variant() {
+        let inner = Inner {
+            value: 0,
+            kind_208: Kind::Empty,
+        };
+        assert_eq!(to_bytes(&inner).unwrap(), &[206, 1, 0, 208, 2, 1, 0][..]);
+    }
+
+    #[test]
+    fn map() {
+        let map: BTreeMap<u64, &str> = [(8, "a"), (300, "b")].into_iter().collect();
+        assert_eq!(
+            to_bytes(&map).unwrap(),
+            &[8, 1, b'a', 0xFD, 1, 44, 1, b'b'][..]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        #[derive(Serialize)]
+        struct NoNumber {
+            name: u8,
+        }
+        #[derive(Serialize)]
+        struct Negative {
+            value_8: i32,
+        }
+
+        assert!(to_bytes(&NoNumber { name: 1 }).is_err());
+        assert!(to_bytes(&Negative { value_8: -1 }).is_err());
+        assert!(to_bytes(&(1, 2)).is_err());
+        assert!(to_bytes(&vec![1, 2]).is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use bytes::{Buf, Bytes};
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::{hex, GenericTlv, NonNegativeInteger, TlvDecode, TlvEncode, VarNum};
+
+/// Deserializes a value from its TLV encoding, which must be consumed entirely
+struct WireVisitor<T> {
+    expecting: &'static str,
+    _marker: PhantomData<T>,
+}
+
+impl<T> WireVisitor<T> {
+    fn new(expecting: &'static str) -> Self {
+        Self {
+            expecting,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: TlvDecode> Visitor<'de> for WireVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+        let mut bytes = Bytes::copy_from_slice(v);
+        let value = T::decode(&mut bytes).map_err(E::custom)?;
+        if bytes.has_remaining() {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        Ok(value)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+/// Human-readable formats use the value as a number, others use the TLV encoding as bytes
+impl Serialize for VarNum {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_u64(self.value())
+        } else {
+            serializer.serialize_bytes(&self.encode())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VarNum {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            u64::deserialize(deserializer).map(VarNum::from)
+        } else {
+            deserializer.deserialize_bytes(WireVisitor::new("an encoded VarNum"))
+        }
+    }
+}
+
+/// Human-readable formats use the value as a number, others use the TLV encoding as bytes, which
+/// preserves the width of the integer
+impl Serialize for NonNegativeInteger {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_u64(self.as_u64())
+        } else {
+            serializer.serialize_bytes(&self.encode())
+        }
+    }
+}
+
+/// Human-readable formats give the value only, so the shortest encoding is chosen
+impl<'de> Deserialize<'de> for NonNegativeInteger {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            u64::deserialize(deserializer).map(NonNegativeInteger::new)
+        } else {
+            deserializer.deserialize_bytes(WireVisitor::new("an encoded NonNegativeInteger"))
+        }
+    }
+}
+
+/// The human-readable form of a [`GenericTlv`]
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "GenericTlv", deny_unknown_fields)]
+struct ReadableTlv {
+    #[serde(rename = "type")]
+    typ: u64,
+    #[serde(default)]
+    length: Option<u64>,
+    content: String,
+}
+
+/// Human-readable formats use a struct with `type`, `length` and hex-encoded `content`, others use
+/// the TLV encoding of the record as bytes
+impl<T: TlvEncode> Serialize for GenericTlv<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            ReadableTlv {
+                typ: self.typ.value(),
+                length: Some(self.len.value()),
+                content: hex::encode(&self.content.encode()),
+            }
+            .serialize(serializer)
+        } else {
+            serializer.serialize_bytes(&self.encode())
+        }
+    }
+}
+
+/// In human-readable formats, `length` may be omitted and defaults to the size of the content
+impl<'de, T: TlvDecode + TlvEncode> Deserialize<'de> for GenericTlv<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            return deserializer.deserialize_bytes(WireVisitor::new("an encoded TLV record"));
+        }
+
+        let readable = ReadableTlv::deserialize(deserializer)?;
+        let mut bytes = Bytes::from(hex::decode(&readable.content).map_err(de::Error::custom)?);
+        let content = T::decode(&mut bytes).map_err(de::Error::custom)?;
+        if bytes.has_remaining() {
+            return Err(de::Error::custom("TLV content has trailing data"));
+        }
+        let size = content.size() as u64;
+        let len = readable.length.unwrap_or(size);
+        if len > size {
+            return Err(de::Error::custom("TLV length exceeds content"));
+        }
+        Ok(Self {
+            typ: readable.typ.into(),
+            len: len.into(),
+            content,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
+
+    use super::*;
+
+    #[test]
+    fn var_num() {
+        let num = VarNum::new(300);
+        assert_tokens(&num.readable(), &[Token::U64(300)]);
+        assert_tokens(&num.compact(), &[Token::Bytes(&[0xFD, 1, 44])]);
+        assert_eq!(serde_json::from_value::<VarNum>(json!(300)).unwrap(), num);
+        assert_de_tokens_error::<serde_test::Compact<VarNum>>(
+            &[Token::Bytes(&[5, 6])],
+            "invalid length 2, expected an encoded VarNum",
+        );
+    }
+
+    #[test]
+    fn non_negative_integer() {
+        let n = NonNegativeInteger::U16(5);
+        assert_tokens(&n.compact(), &[Token::Bytes(&[0, 5])]);
+        assert_eq!(serde_json::to_value(n).unwrap(), json!(5));
+        assert_eq!(
+            serde_json::from_value::<NonNegativeInteger>(json!(5)).unwrap(),
+            NonNegativeInteger::U8(5)
+        );
+        assert_de_tokens_error::<serde_test::Compact<NonNegativeInteger>>(
+            &[Token::Bytes(&[0, 0, 5])],
+            &crate::TlvError::UnexpectedLength.to_string(),
+        );
+    }
+
+    #[test]
+    fn generic_tlv() {
+        let tlv = GenericTlv {
+            typ: VarNum::new(8),
+            len: VarNum::new(2),
+            content: Bytes::from_static(&[0xAB, 0xCD]),
+        };
+        let json = json!({ "type": 8, "length": 2, "content": "abcd" });
+        assert_eq!(serde_json::to_value(&tlv).unwrap(), json);
+        assert_eq!(
+            serde_json::from_value::<GenericTlv<Bytes>>(json).unwrap(),
+            tlv
+        );
+        assert_eq!(
+            serde_json::from_value::<GenericTlv<Bytes>>(json!({ "type": 8, "content": "abcd" }))
+                .unwrap(),
+            tlv
+        );
+        assert!(serde_json::from_value::<GenericTlv<Bytes>>(
+            json!({ "type": 8, "length": 3, "content": "abcd" })
+        )
+        .is_err());
+
+        assert_tokens(&tlv.clone().compact(), &[Token::Bytes(&[8, 2, 0xAB, 0xCD])]);
+    }
+
+    #[test]
+    fn generic_tlv_nested() {
+        let tlv = GenericTlv {
+            typ: VarNum::new(200),
+            len: VarNum::new(1),
+            content: NonNegativeInteger::U8(7),
+        };
+        let json = serde_json::to_value(&tlv).unwrap();
+        assert_eq!(json, json!({ "type": 200, "length": 1, "content": "07" }));
+        assert_eq!(
+            serde_json::from_value::<GenericTlv<NonNegativeInteger>>(json).unwrap(),
+            tlv
+        );
+        assert_tokens(&tlv.compact(), &[Token::Bytes(&[200, 1, 7])]);
+    }
+
+    #[test]
+    fn tlv_format() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Record {
+            tlv_300: GenericTlv<Bytes>,
+            count_200: NonNegativeInteger,
+            typ_202: VarNum,
+        }
+
+        let record = Record {
+            tlv_300: GenericTlv {
+                typ: VarNum::new(8),
+                len: VarNum::new(2),
+                content: Bytes::from_static(&[0xAB, 0xCD]),
+            },
+            count_200: NonNegativeInteger::U16(5),
+            typ_202: VarNum::new(300),
+        };
+        let wire = crate::to_bytes(&record).unwrap();
+        assert_eq!(
+            wire,
+            &[0xFD, 1, 44, 4, 8, 2, 0xAB, 0xCD, 200, 2, 0, 5, 202, 3, 0xFD, 1, 44][..]
+        );
+        assert_eq!(crate::from_bytes::<Record>(wire).unwrap(), record);
+    }
+}
@@ -0,0 +1,778 @@
+use core::time::Duration;
+
+use bytes::{BufMut, Bytes};
+
+use crate::tlv::{decode_record, value_record};
+use crate::{
+    decode_ordered, encode_to_bytes, required_field, DecodeConfig, Name, NonNegativeInteger,
+    Result, Tlv, TlvDecode, TlvEncode, TlvError, UnknownTlvs, VarNum,
+};
+
+/// The algorithm used to create a signature
+///
+/// Numbers without a variant of their own are kept as [`SignatureType::Other`]. Use
+/// [`From<u64>`](SignatureType::from) to convert numbers, so that known numbers map to their
+/// variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureType {
+    /// A SHA-256 digest without a key (0)
+    DigestSha256,
+    /// An RSA signature over a SHA-256 digest (1)
+    Sha256WithRsa,
+    /// An ECDSA signature over a SHA-256 digest (3)
+    Sha256WithEcdsa,
+    /// An HMAC using SHA-256 (4)
+    HmacWithSha256,
+    /// An Ed25519 signature (5)
+    Ed25519,
+    /// Any other signature type
+    Other(u64),
+}
+
+impl SignatureType {
+    /// The number of the signature type
+    pub const fn value(&self) -> u64 {
+        match self {
+            Self::DigestSha256 => 0,
+            Self::Sha256WithRsa => 1,
+            Self::Sha256WithEcdsa => 3,
+            Self::HmacWithSha256 => 4,
+            Self::Ed25519 => 5,
+            Self::Other(value) => *value,
+        }
+    }
+}
+
+impl From<u64> for SignatureType {
+    fn from(value: u64) -> Self {
+        match value {
+            0 => Self::DigestSha256,
+            1 => Self::Sha256WithRsa,
+            3 => Self::Sha256WithEcdsa,
+            4 => Self::HmacWithSha256,
+            5 => Self::Ed25519,
+            value => Self::Other(value),
+        }
+    }
+}
+
+impl From<SignatureType> for u64 {
+    fn from(signature_type: SignatureType) -> Self {
+        signature_type.value()
+    }
+}
+
+/// The encoding of a [`SignatureType`]
+struct SignatureTypeRecord(NonNegativeInteger);
+
+value_record!(SignatureTypeRecord, 27);
+
+impl Tlv for SignatureType {
+    const TYP: usize = SignatureTypeRecord::TYP;
+
+    fn inner_size(&self) -> usize {
+        NonNegativeInteger::new(self.value()).size()
+    }
+}
+
+impl TlvEncode for SignatureType {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        SignatureTypeRecord(NonNegativeInteger::new(self.value())).encode_to(buf);
+    }
+
+    fn size(&self) -> usize {
+        SignatureTypeRecord(NonNegativeInteger::new(self.value())).size()
+    }
+}
+
+impl TlvDecode for SignatureType {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        SignatureTypeRecord::decode_with(bytes, config).map(|record| record.0.as_u64().into())
+    }
+}
+
+/// The SHA-256 digest of a public key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyDigest(pub Bytes);
+
+value_record!(KeyDigest, 29);
+
+/// Identifies the key that can verify a signature
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KeyLocator {
+    /// The name of the key, or of a certificate for it
+    Name(Name),
+    /// The digest of the key
+    KeyDigest(KeyDigest),
+}
+
+impl Tlv for KeyLocator {
+    const TYP: usize = 28;
+
+    fn inner_size(&self) -> usize {
+        match self {
+            Self::Name(name) => name.size(),
+            Self::KeyDigest(digest) => digest.size(),
+        }
+    }
+}
+
+impl TlvEncode for KeyLocator {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        VarNum::from(Self::TYP).encode_to(buf);
+        VarNum::from(self.inner_size()).encode_to(buf);
+        match self {
+            Self::Name(name) => name.encode_to(buf),
+            Self::KeyDigest(digest) => digest.encode_to(buf),
+        }
+    }
+
+    fn size(&self) -> usize {
+        let inner_size = self.inner_size();
+        VarNum::from(Self::TYP).size() + VarNum::from(inner_size).size() + inner_size
+    }
+}
+
+/// Fails with [`TlvError::FormatError`] if the KeyLocator contains neither a Name nor a KeyDigest,
+/// and with [`TlvError::UnexpectedLength`] if anything follows it
+impl TlvDecode for KeyLocator {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        decode_record(bytes, config, Some(Self::TYP), |_, mut inner| {
+            let config = config.nested()?;
+            let len = inner.len();
+            let typ = usize::from(VarNum::decode_with(&mut inner.clone(), &config)?);
+            let key_locator = match typ {
+                Name::TYP => Name::decode_with(&mut inner, &config).map(Self::Name),
+                KeyDigest::TYP => KeyDigest::decode_with(&mut inner, &config).map(Self::KeyDigest),
+                _ => Err(TlvError::FormatError),
+            }?;
+            if !inner.is_empty() {
+                return Err(TlvError::UnexpectedLength.at(len - inner.len()));
+            }
+            Ok(key_locator)
+        })
+        .map_err(|e| e.in_tlv(Some("KeyLocator"), Self::TYP))
+    }
+}
+
+/// The start of a validity period, in UTC as `YYYYMMDDThhmmss`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NotBefore(pub Bytes);
+
+value_record!(NotBefore, 254);
+
+/// The end of a validity period, in UTC as `YYYYMMDDThhmmss`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NotAfter(pub Bytes);
+
+value_record!(NotAfter, 255);
+
+/// The period in which a certificate is valid
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidityPeriod {
+    /// The first moment of the period
+    pub not_before: NotBefore,
+    /// The last moment of the period
+    pub not_after: NotAfter,
+}
+
+impl ValidityPeriod {
+    /// The TLV-TYPEs of the elements of a ValidityPeriod, in the order they must appear
+    const ELEMENTS: [usize; 2] = [NotBefore::TYP, NotAfter::TYP];
+}
+
+impl Tlv for ValidityPeriod {
+    const TYP: usize = 253;
+
+    fn inner_size(&self) -> usize {
+        self.not_before.size() + self.not_after.size()
+    }
+}
+
+impl TlvEncode for ValidityPeriod {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        VarNum::from(Self::TYP).encode_to(buf);
+        VarNum::from(self.inner_size()).encode_to(buf);
+        self.not_before.encode_to(buf);
+        self.not_after.encode_to(buf);
+    }
+
+    fn size(&self) -> usize {
+        let inner_size = self.inner_size();
+        VarNum::from(Self::TYP).size() + VarNum::from(inner_size).size() + inner_size
+    }
+}
+
+/// Unknown non-critical elements are skipped, and a missing NotBefore or NotAfter leads to
+/// [`TlvError::MissingField`]
+impl TlvDecode for ValidityPeriod {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        decode_record(bytes, config, Some(Self::TYP), |_, mut inner| {
+            let config = config.nested()?;
+            let (mut not_before, mut not_after) = (None, None);
+            decode_ordered(&mut inner, &config, &Self::ELEMENTS, None, |typ, record| {
+                match typ {
+                    NotBefore::TYP => not_before = Some(TlvDecode::decode_with(record, &config)?),
+                    NotAfter::TYP => not_after = Some(TlvDecode::decode_with(record, &config)?),
+                    _ => unreachable!("unknown elements are skipped"),
+                }
+                Ok(())
+            })?;
+            Ok(Self {
+                not_before: required_field(not_before, NotBefore::TYP)?,
+                not_after: required_field(not_after, NotAfter::TYP)?,
+            })
+        })
+        .map_err(|e| e.in_tlv(Some("ValidityPeriod"), Self::TYP))
+    }
+}
+
+/// Information about the signature of a Data packet
+///
+/// Unknown non-critical elements are kept in [`extra`](SignatureInfo::extra) and written back when
+/// encoding, in their original position. Known elements are encoded in their shortest form, so a
+/// decoded SignatureInfo only encodes to the same bytes if its numbers and lengths were minimally
+/// encoded: a SignatureType of `[27, 2, 0, 1]` is written back as `[27, 1, 1]`. Decoded
+/// [`Data`](crate::Data) packets keep their original bytes, so this does not affect verifying them.
+/// When decoding, known elements that are out of order or duplicated lead to an error, as do
+/// unknown critical elements.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SignatureInfo {
+    /// The signature algorithm
+    pub signature_type: SignatureType,
+    /// The key that can verify the signature
+    pub key_locator: Option<KeyLocator>,
+    /// The validity period of a certificate
+    pub validity_period: Option<ValidityPeriod>,
+    /// Unknown non-critical elements
+    pub extra: UnknownTlvs,
+}
+
+impl SignatureInfo {
+    /// The TLV-TYPEs of the elements of a SignatureInfo, in the order they must appear
+    const ELEMENTS: [usize; 3] = [SignatureType::TYP, KeyLocator::TYP, ValidityPeriod::TYP];
+
+    /// Create a SignatureInfo for `signature_type` without a key locator
+    pub fn new(signature_type: SignatureType) -> Self {
+        Self {
+            signature_type,
+            key_locator: None,
+            validity_period: None,
+            extra: UnknownTlvs::new(),
+        }
+    }
+
+    /// Set the key that can verify the signature
+    pub fn with_key_locator(mut self, key_locator: KeyLocator) -> Self {
+        self.key_locator = Some(key_locator);
+        self
+    }
+
+    /// Set the validity period of a certificate
+    pub fn with_validity_period(mut self, validity_period: ValidityPeriod) -> Self {
+        self.validity_period = Some(validity_period);
+        self
+    }
+}
+
+impl Tlv for SignatureInfo {
+    const TYP: usize = 22;
+
+    fn inner_size(&self) -> usize {
+        self.signature_type.size()
+            + self.key_locator.size()
+            + self.validity_period.size()
+            + self.extra.size()
+    }
+}
+
+impl TlvEncode for SignatureInfo {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        VarNum::from(Self::TYP).encode_to(buf);
+        VarNum::from(self.inner_size()).encode_to(buf);
+        self.extra.encode_at(0, buf);
+        self.signature_type.encode_to(buf);
+        self.extra.encode_at(1, buf);
+        self.key_locator.encode_to(buf);
+        self.extra.encode_at(2, buf);
+        self.validity_period.encode_to(buf);
+        self.extra.encode_at(3, buf);
+    }
+
+    fn size(&self) -> usize {
+        let inner_size = self.inner_size();
+        VarNum::from(Self::TYP).size() + VarNum::from(inner_size).size() + inner_size
+    }
+}
+
+impl TlvDecode for SignatureInfo {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        decode_record(bytes, config, Some(Self::TYP), |_, mut inner| {
+            let config = config.nested()?;
+
+            let (mut signature_type, mut key_locator, mut validity_period) = (None, None, None);
+            let mut extra = UnknownTlvs::new();
+            decode_ordered(
+                &mut inner,
+                &config,
+                &Self::ELEMENTS,
+                Some(&mut extra),
+                |typ, record| {
+                    match typ {
+                        SignatureType::TYP => {
+                            signature_type = Some(TlvDecode::decode_with(record, &config)?)
+                        }
+                        KeyLocator::TYP => {
+                            key_locator = Some(TlvDecode::decode_with(record, &config)?)
+                        }
+                        ValidityPeriod::TYP => {
+                            validity_period = Some(TlvDecode::decode_with(record, &config)?)
+                        }
+                        _ => unreachable!("unknown elements are kept in extra"),
+                    }
+                    Ok(())
+                },
+            )?;
+            Ok(Self {
+                signature_type: required_field(signature_type, SignatureType::TYP)?,
+                key_locator,
+                validity_period,
+                extra,
+            })
+        })
+        .map_err(|e| e.in_tlv(Some("SignatureInfo"), Self::TYP))
+    }
+}
+
+/// The signature of a Data packet
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SignatureValue(pub Bytes);
+
+value_record!(SignatureValue, 23);
+
+/// A random value that makes each signed Interest unique
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SignatureNonce(pub Bytes);
+
+value_record!(SignatureNonce, 38);
+
+/// When a signed Interest was signed, in milliseconds since the Unix epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignatureTime(pub NonNegativeInteger);
+
+value_record!(SignatureTime, 40);
+
+impl From<Duration> for SignatureTime {
+    fn from(time: Duration) -> Self {
+        Self(NonNegativeInteger::new(
+            time.as_millis().try_into().unwrap_or(u64::MAX),
+        ))
+    }
+}
+
+impl From<SignatureTime> for Duration {
+    fn from(time: SignatureTime) -> Self {
+        Duration::from_millis(time.0.as_u64())
+    }
+}
+
+/// A number that increases with each Interest signed with the same key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignatureSeqNum(pub NonNegativeInteger);
+
+value_record!(SignatureSeqNum, 42);
+
+/// Information about the signature of a signed Interest
+///
+/// In addition to the elements of a [`SignatureInfo`], this may contain a nonce, a timestamp and a
+/// sequence number, which allow the producer to reject replayed Interests. Unknown non-critical
+/// elements are kept in [`extra`](InterestSignatureInfo::extra), and known elements are encoded in
+/// their shortest form, as in [`SignatureInfo`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InterestSignatureInfo {
+    /// The signature algorithm
+    pub signature_type: SignatureType,
+    /// The key that can verify the signature
+    pub key_locator: Option<KeyLocator>,
+    /// A random value unique to this Interest
+    pub nonce: Option<SignatureNonce>,
+    /// When the Interest was signed
+    pub time: Option<SignatureTime>,
+    /// The position of this Interest among those signed with the same key
+    pub seq_num: Option<SignatureSeqNum>,
+    /// Unknown non-critical elements
+    pub extra: UnknownTlvs,
+}
+
+impl InterestSignatureInfo {
+    /// The TLV-TYPEs of the elements of an InterestSignatureInfo, in the order they must appear
+    const ELEMENTS: [usize; 5] = [
+        SignatureType::TYP,
+        KeyLocator::TYP,
+        SignatureNonce::TYP,
+        SignatureTime::TYP,
+        SignatureSeqNum::TYP,
+    ];
+
+    /// Create an InterestSignatureInfo for `signature_type` without any other elements
+    pub fn new(signature_type: SignatureType) -> Self {
+        Self {
+            signature_type,
+            key_locator: None,
+            nonce: None,
+            time: None,
+            seq_num: None,
+            extra: UnknownTlvs::new(),
+        }
+    }
+
+    /// Set the key that can verify the signature
+    pub fn with_key_locator(mut self, key_locator: KeyLocator) -> Self {
+        self.key_locator = Some(key_locator);
+        self
+    }
+
+    /// Set the nonce
+    pub fn with_nonce(mut self, nonce: impl Into<Bytes>) -> Self {
+        self.nonce = Some(SignatureNonce(nonce.into()));
+        self
+    }
+
+    /// Set when the Interest was signed, as the time since the Unix epoch
+    pub fn with_time(mut self, time: Duration) -> Self {
+        self.time = Some(time.into());
+        self
+    }
+
+    /// Set the sequence number
+    pub fn with_seq_num(mut self, seq_num: u64) -> Self {
+        self.seq_num = Some(SignatureSeqNum(NonNegativeInteger::new(seq_num)));
+        self
+    }
+}
+
+impl Tlv for InterestSignatureInfo {
+    const TYP: usize = 44;
+
+    fn inner_size(&self) -> usize {
+        self.signature_type.size()
+            + self.key_locator.size()
+            + self.nonce.size()
+            + self.time.size()
+            + self.seq_num.size()
+            + self.extra.size()
+    }
+}
+
+impl TlvEncode for InterestSignatureInfo {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        VarNum::from(Self::TYP).encode_to(buf);
+        VarNum::from(self.inner_size()).encode_to(buf);
+        self.extra.encode_at(0, buf);
+        self.signature_type.encode_to(buf);
+        self.extra.encode_at(1, buf);
+        self.key_locator.encode_to(buf);
+        self.extra.encode_at(2, buf);
+        self.nonce.encode_to(buf);
+        self.extra.encode_at(3, buf);
+        self.time.encode_to(buf);
+        self.extra.encode_at(4, buf);
+        self.seq_num.encode_to(buf);
+        self.extra.encode_at(5, buf);
+    }
+
+    fn size(&self) -> usize {
+        let inner_size = self.inner_size();
+        VarNum::from(Self::TYP).size() + VarNum::from(inner_size).size() + inner_size
+    }
+}
+
+impl TlvDecode for InterestSignatureInfo {
+    fn decode(bytes: &mut Bytes) -> Result<Self> {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        decode_record(bytes, config, Some(Self::TYP), |_, mut inner| {
+            let config = config.nested()?;
+
+            let mut signature_type = None;
+            let mut info = Self::new(SignatureType::DigestSha256);
+            decode_ordered(
+                &mut inner,
+                &config,
+                &Self::ELEMENTS,
+                Some(&mut info.extra),
+                |typ, record| {
+                    match typ {
+                        SignatureType::TYP => {
+                            signature_type = Some(TlvDecode::decode_with(record, &config)?)
+                        }
+                        KeyLocator::TYP => {
+                            info.key_locator = Some(TlvDecode::decode_with(record, &config)?)
+                        }
+                        SignatureNonce::TYP => {
+                            info.nonce = Some(TlvDecode::decode_with(record, &config)?)
+                        }
+                        SignatureTime::TYP => {
+                            info.time = Some(TlvDecode::decode_with(record, &config)?)
+                        }
+                        SignatureSeqNum::TYP => {
+                            info.seq_num = Some(TlvDecode::decode_with(record, &config)?)
+                        }
+                        _ => unreachable!("unknown elements are kept in extra"),
+                    }
+                    Ok(())
+                },
+            )?;
+            info.signature_type = required_field(signature_type, SignatureType::TYP)?;
+            Ok(info)
+        })
+        .map_err(|e| e.in_tlv(Some("InterestSignatureInfo"), Self::TYP))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn signature_type() {
+        for value in [0, 1, 3, 4, 5, 2, 200, u64::MAX] {
+            let signature_type = SignatureType::from(value);
+            assert_eq!(signature_type.value(), value);
+            assert_eq!(
+                SignatureType::decode(&mut signature_type.encode()).unwrap(),
+                signature_type
+            );
+        }
+        assert_eq!(SignatureType::from(3), SignatureType::Sha256WithEcdsa);
+        assert_eq!(SignatureType::from(2), SignatureType::Other(2));
+        assert_eq!(SignatureType::Ed25519.encode(), &[27, 1, 5][..]);
+        assert_eq!(
+            SignatureType::decode(&mut Bytes::from_static(&[27, 2, 0, 1])).unwrap(),
+            SignatureType::Sha256WithRsa
+        );
+    }
+
+    #[test]
+    fn key_locator() {
+        let name = KeyLocator::Name("/key".parse().unwrap());
+        let wire = [28, 7, 7, 5, 8, 3, b'k', b'e', b'y'];
+        assert_eq!(name.encode(), &wire[..]);
+        assert_eq!(
+            KeyLocator::decode(&mut Bytes::copy_from_slice(&wire)).unwrap(),
+            name
+        );
+
+        let digest = KeyLocator::KeyDigest(KeyDigest(Bytes::from_static(&[1, 2])));
+        let wire = [28, 4, 29, 2, 1, 2];
+        assert_eq!(digest.encode(), &wire[..]);
+        assert_eq!(
+            KeyLocator::decode(&mut Bytes::copy_from_slice(&wire)).unwrap(),
+            digest
+        );
+
+        let error = KeyLocator::decode(&mut Bytes::from_static(&[28, 2, 8, 0])).unwrap_err();
+        assert_eq!(error, TlvError::FormatError);
+        assert_eq!(
+            error.to_string(),
+            "Data had unexpected format at byte 2 in KeyLocator(28)"
+        );
+
+        let error =
+            KeyLocator::decode(&mut Bytes::from_static(&[28, 6, 29, 2, 1, 2, 200, 0])).unwrap_err();
+        assert_eq!(error, TlvError::UnexpectedLength);
+        assert_eq!(error.context().unwrap().offset, Some(6));
+    }
+
+    #[test]
+    fn signature_info() {
+        #[rustfmt::skip]
+        let wire: &[u8] = &[
+            22, 54,
+                27, 1, 3,
+                28, 7, 7, 5, 8, 3, b'k', b'e', b'y',
+                0xFD, 0, 0xFD, 38,
+                    0xFD, 0, 0xFE, 15, b'2', b'0', b'2', b'4', b'0', b'1', b'0', b'1',
+                        b'T', b'0', b'0', b'0', b'0', b'0', b'0',
+                    0xFD, 0, 0xFF, 15, b'2', b'0', b'2', b'5', b'0', b'1', b'0', b'1',
+                        b'T', b'0', b'0', b'0', b'0', b'0', b'0',
+        ];
+        let info = SignatureInfo::new(SignatureType::Sha256WithEcdsa)
+            .with_key_locator(KeyLocator::Name("/key".parse().unwrap()))
+            .with_validity_period(ValidityPeriod {
+                not_before: NotBefore(Bytes::from_static(b"20240101T000000")),
+                not_after: NotAfter(Bytes::from_static(b"20250101T000000")),
+            });
+        assert_eq!(info.encode(), wire);
+        assert_eq!(info.size(), wire.len());
+        assert_eq!(
+            SignatureInfo::decode(&mut Bytes::from_static(wire)).unwrap(),
+            info
+        );
+
+        let info = SignatureInfo::new(SignatureType::DigestSha256);
+        assert_eq!(info.encode(), &[22, 3, 27, 1, 0][..]);
+    }
+
+    #[test]
+    fn non_minimal_elements() {
+        let wire = [22, 4, 27, 2, 0, 1];
+        let info = SignatureInfo::decode(&mut Bytes::copy_from_slice(&wire)).unwrap();
+        assert_eq!(info.signature_type, SignatureType::Sha256WithRsa);
+        assert_eq!(info.encode(), &[22, 3, 27, 1, 1][..]);
+    }
+
+    #[test]
+    fn unknown_elements() {
+        #[rustfmt::skip]
+        let wire: &[u8] = &[
+            22, 17,
+                200, 1, 1,
+                27, 1, 5,
+                202, 0,
+                28, 4, 29, 2, 1, 2,
+                204, 1, 4,
+        ];
+        let info = SignatureInfo::decode(&mut Bytes::from_static(wire)).unwrap();
+        assert_eq!(info.signature_type, SignatureType::Ed25519);
+        assert_eq!(info.extra.len(), 3);
+        assert_eq!(info.encode(), wire);
+        assert_eq!(info.size(), wire.len());
+    }
+
+    #[test]
+    fn interest_signature_info() {
+        #[rustfmt::skip]
+        let wire: &[u8] = &[
+            44, 26,
+                27, 1, 4,
+                28, 4, 29, 2, 1, 2,
+                38, 4, 1, 2, 3, 4,
+                200, 0,
+                40, 4, 0x65, 0x92, 0x00, 0x80,
+                42, 1, 7,
+        ];
+        let info = InterestSignatureInfo::decode(&mut Bytes::from_static(wire)).unwrap();
+        assert_eq!(info.signature_type, SignatureType::HmacWithSha256);
+        assert_eq!(
+            info.nonce,
+            Some(SignatureNonce(Bytes::from_static(&[1, 2, 3, 4])))
+        );
+        assert_eq!(
+            info.time.map(Duration::from),
+            Some(Duration::from_millis(1_704_067_200))
+        );
+        assert_eq!(info.extra.len(), 1);
+        assert_eq!(info.encode(), wire);
+        assert_eq!(info.size(), wire.len());
+
+        let built = InterestSignatureInfo::new(SignatureType::HmacWithSha256)
+            .with_key_locator(KeyLocator::KeyDigest(KeyDigest(Bytes::from_static(&[
+                1, 2,
+            ]))))
+            .with_nonce(&[1, 2, 3, 4][..])
+            .with_time(Duration::from_millis(1_704_067_200))
+            .with_seq_num(7);
+        assert_eq!(
+            InterestSignatureInfo {
+                extra: info.extra.clone(),
+                ..built
+            },
+            info
+        );
+
+        let error = InterestSignatureInfo::decode(&mut Bytes::from_static(&[
+            44, 9, 27, 1, 0, 42, 1, 1, 40, 1, 1,
+        ]))
+        .unwrap_err();
+        assert_eq!(error, TlvError::OutOfOrder { typ: 40 });
+        assert_eq!(
+            error.to_string(),
+            "TLV record of type 40 is out of order at byte 8 in InterestSignatureInfo(44)"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let cases: [(&[u8], TlvError); 6] = [
+            (&[22, 0], TlvError::MissingField { typ: 27 }),
+            (
+                &[22, 9, 28, 4, 29, 2, 1, 2, 27, 1, 0],
+                TlvError::OutOfOrder { typ: 27 },
+            ),
+            (
+                &[22, 6, 28, 4, 29, 2, 1, 2],
+                TlvError::MissingField { typ: 27 },
+            ),
+            (
+                &[22, 6, 27, 1, 0, 27, 1, 0],
+                TlvError::DuplicateField { typ: 27 },
+            ),
+            (
+                &[22, 15, 27, 1, 0, 28, 4, 29, 2, 1, 2, 28, 4, 29, 2, 1, 2],
+                TlvError::DuplicateField { typ: 28 },
+            ),
+            (
+                &[22, 5, 27, 1, 0, 201, 0],
+                TlvError::UnknownCritical { typ: 201 },
+            ),
+        ];
+        for (wire, error) in cases {
+            assert_eq!(
+                SignatureInfo::decode(&mut Bytes::copy_from_slice(wire)).unwrap_err(),
+                error
+            );
+        }
+
+        let error =
+            SignatureInfo::decode(&mut Bytes::from_static(&[22, 5, 27, 1, 0, 201, 0])).unwrap_err();
+        assert_eq!(error.context().unwrap().offset, Some(5));
+
+        let error = SignatureInfo::decode(&mut Bytes::from_static(&[22, 7, 27, 1, 0, 28, 2, 8, 0]))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Data had unexpected format at byte 7 in SignatureInfo(22) > KeyLocator(28)"
+        );
+    }
+}
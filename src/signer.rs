@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{KeyLocator, Result, SignatureType};
+
+/// Creates the signatures of packets
+///
+/// With the `crypto` feature, implementations are provided for the signature types assigned by the
+/// packet format.
+pub trait Signer {
+    /// The type of the signatures created
+    fn signature_type(&self) -> SignatureType;
+
+    /// The key locator to put in the SignatureInfo
+    ///
+    /// The default implementation returns `None`.
+    fn key_locator(&self) -> Option<KeyLocator> {
+        None
+    }
+
+    /// Sign `signed_portion`, returning the value of the SignatureValue
+    fn sign(&self, signed_portion: &[u8]) -> Result<Bytes>;
+}
+
+/// Checks the signatures of packets
+pub trait Verifier {
+    /// The type of the signatures checked
+    fn signature_type(&self) -> SignatureType;
+
+    /// Whether `signature` is a valid signature of `signed_portion`
+    fn verify(&self, signed_portion: &[u8], signature: &[u8]) -> bool;
+}
@@ -1,8 +1,11 @@
+#[cfg(feature = "std")]
 use std::io::Read;
 
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{Buf, BufMut, Bytes};
 
-use crate::{TlvDecode, TlvEncode, TlvError, VarNum};
+#[cfg(feature = "std")]
+use crate::reader::{read_record, DEFAULT_MAX_RECORD_SIZE};
+use crate::{encode_to_bytes, DecodeConfig, TlvDecode, TlvDecodeRef, TlvEncode, TlvError, VarNum};
 
 /// A TLV record
 pub trait Tlv {
@@ -21,40 +24,17 @@ pub trait Tlv {
     }
 
     /// Read a TLV from a type implementing `Read`
-    fn from_reader(mut reader: impl Read) -> Result<Self, TlvError>
+    ///
+    /// Exactly one record is read, so `reader` can be used to read further data afterwards.
+    /// Records larger than [`DEFAULT_MAX_RECORD_SIZE`] are rejected. To read a stream of records,
+    /// use [`TlvReader`](crate::TlvReader) instead.
+    #[cfg(feature = "std")]
+    fn from_reader(reader: impl Read) -> Result<Self, TlvError>
     where
         Self: TlvDecode,
     {
-        let mut header_buf = [0; 18];
-        let bytes_read = reader.read(&mut header_buf).map_err(TlvError::IOError)?;
-        let mut header_bytes = Bytes::copy_from_slice(&header_buf);
-
-        let typ = VarNum::decode(&mut header_bytes)?;
-        if typ.value() as usize != Self::TYP {
-            // Technically not necessary, but we can exit early here
-            return Err(TlvError::TypeMismatch {
-                expected: Self::TYP,
-                found: typ.value() as usize,
-            });
-        }
-
-        let len = VarNum::decode(&mut header_bytes)?;
-        let total_len = typ.size() + len.size() + len.value() as usize;
-
-        let mut bytes = BytesMut::with_capacity(total_len);
-        bytes.put(&header_buf[0..bytes_read]);
-
-        let mut left_to_read = total_len - bytes_read;
-        let mut buf = [0; 1024];
-        while left_to_read > 0 {
-            let bytes_read = reader
-                .read(&mut buf[0..left_to_read])
-                .map_err(TlvError::IOError)?;
-            bytes.put(&buf[..left_to_read]);
-            left_to_read -= bytes_read;
-        }
-
-        Self::decode(&mut bytes.freeze())
+        let mut bytes = read_record(reader, Some(Self::TYP), DEFAULT_MAX_RECORD_SIZE)?;
+        Self::decode(&mut bytes)
     }
 }
 
@@ -74,6 +54,178 @@ pub const fn tlv_typ_critical(typ: usize) -> bool {
     typ < 32 || typ & 1 == 1
 }
 
+/// Split the next TLV record off `bytes`, returning its type and value
+///
+/// Errors are annotated with the position of the invalid header field, relative to the start of
+/// `bytes`.
+pub(crate) fn split_record(
+    bytes: &mut Bytes,
+    config: &DecodeConfig,
+) -> Result<(VarNum, Bytes), TlvError> {
+    let start_len = bytes.len();
+    let typ = VarNum::decode_with(bytes, config).map_err(|e| e.at(0))?;
+    let len_start = start_len - bytes.len();
+    let len = VarNum::decode_with(bytes, config).map_err(|e| e.at(len_start))?;
+    config
+        .check_tlv_length(len.value())
+        .map_err(|e| e.at(len_start))?;
+    if bytes.remaining() < len.into() {
+        return Err(TlvError::UnexpectedEndOfStream.at(len_start));
+    }
+    Ok((typ, bytes.split_to(len.into())))
+}
+
+/// Return [`TlvError::TypeMismatch`] unless `found` is the `expected` type
+pub(crate) fn check_type(found: usize, expected: usize) -> Result<(), TlvError> {
+    if found != expected {
+        return Err(TlvError::TypeMismatch { expected, found });
+    }
+    Ok(())
+}
+
+/// Decode the TLV record at the start of `bytes`, passing its type and value to `decode_value`
+///
+/// The total length limit is checked first and, if `expected` is given, the TLV-TYPE before
+/// anything is consumed. Errors of `decode_value` are positioned within the value, and all errors
+/// are made relative to the start of `bytes`.
+pub(crate) fn decode_record<T>(
+    bytes: &mut Bytes,
+    config: &DecodeConfig,
+    expected: Option<usize>,
+    decode_value: impl FnOnce(VarNum, Bytes) -> Result<T, TlvError>,
+) -> Result<T, TlvError> {
+    let start_len = bytes.len();
+    let decode = || {
+        config.check_total_length(start_len)?;
+        if let Some(expected) = expected {
+            let typ = VarNum::decode_with(&mut bytes.clone(), config)?;
+            check_type(typ.into(), expected)?;
+        }
+        let (typ, value) = split_record(bytes, config)?;
+        let (value_start, value_len) = (start_len - bytes.len() - value.len(), value.len());
+        decode_value(typ, value).map_err(|e| e.within(value_start, value_len))
+    };
+    decode().map_err(|e| e.relative_to(start_len))
+}
+
+/// Implement [`Tlv`], [`TlvEncode`] and [`TlvDecode`] for a tuple struct `$ty` that is a record of
+/// type `$typ` holding a single value
+///
+/// Unlike with the derive macro, the value is written directly into the buffer when encoding, and
+/// decoded with the caller's configuration. Anything left in the record after the value is an
+/// error.
+macro_rules! value_record {
+    ($ty:ident, $typ:expr) => {
+        impl $crate::Tlv for $ty {
+            const TYP: usize = $typ;
+
+            fn inner_size(&self) -> usize {
+                $crate::TlvEncode::size(&self.0)
+            }
+        }
+
+        impl $crate::TlvEncode for $ty {
+            fn encode(&self) -> ::bytes::Bytes {
+                $crate::encode_to_bytes(self)
+            }
+
+            fn encode_to(&self, buf: &mut impl ::bytes::BufMut) {
+                $crate::TlvEncode::encode_to(&$crate::VarNum::from(Self::TYP), buf);
+                let len = $crate::VarNum::from($crate::Tlv::inner_size(self));
+                $crate::TlvEncode::encode_to(&len, buf);
+                $crate::TlvEncode::encode_to(&self.0, buf);
+            }
+
+            fn size(&self) -> usize {
+                let inner_size = $crate::Tlv::inner_size(self);
+                $crate::TlvEncode::size(&$crate::VarNum::from(Self::TYP))
+                    + $crate::TlvEncode::size(&$crate::VarNum::from(inner_size))
+                    + inner_size
+            }
+        }
+
+        impl $crate::TlvDecode for $ty {
+            fn decode(bytes: &mut ::bytes::Bytes) -> $crate::Result<Self> {
+                Self::decode_with(bytes, &$crate::DecodeConfig::default())
+            }
+
+            fn decode_with(
+                bytes: &mut ::bytes::Bytes,
+                config: &$crate::DecodeConfig,
+            ) -> $crate::Result<Self> {
+                $crate::tlv::decode_record(bytes, config, Some(Self::TYP), |_, mut value| {
+                    let len = value.len();
+                    let inner = config
+                        .nested()
+                        .and_then(|config| $crate::TlvDecode::decode_with(&mut value, &config))?;
+                    if !value.is_empty() {
+                        return Err($crate::TlvError::UnexpectedLength.at(len - value.len()));
+                    }
+                    Ok(Self(inner))
+                })
+                .map_err(|e| e.in_tlv(Some(stringify!($ty)), Self::TYP))
+            }
+        }
+    };
+}
+pub(crate) use value_record;
+
+/// Implement [`Tlv`], [`TlvEncode`] and [`TlvDecode`] for a unit struct `$ty` that is a record of
+/// type `$typ` with an empty value
+///
+/// A record of that type with a non-empty value fails to decode with
+/// [`TlvError::UnexpectedLength`].
+macro_rules! empty_record {
+    ($ty:ident, $typ:expr) => {
+        impl $crate::Tlv for $ty {
+            const TYP: usize = $typ;
+
+            fn inner_size(&self) -> usize {
+                0
+            }
+        }
+
+        impl $crate::TlvEncode for $ty {
+            fn encode(&self) -> ::bytes::Bytes {
+                $crate::encode_to_bytes(self)
+            }
+
+            fn encode_to(&self, buf: &mut impl ::bytes::BufMut) {
+                $crate::TlvEncode::encode_to(&$crate::VarNum::from(Self::TYP), buf);
+                let len = $crate::VarNum::from($crate::Tlv::inner_size(self));
+                $crate::TlvEncode::encode_to(&len, buf);
+            }
+
+            fn size(&self) -> usize {
+                let inner_size = $crate::Tlv::inner_size(self);
+                $crate::TlvEncode::size(&$crate::VarNum::from(Self::TYP))
+                    + $crate::TlvEncode::size(&$crate::VarNum::from(inner_size))
+                    + inner_size
+            }
+        }
+
+        impl $crate::TlvDecode for $ty {
+            fn decode(bytes: &mut ::bytes::Bytes) -> $crate::Result<Self> {
+                Self::decode_with(bytes, &$crate::DecodeConfig::default())
+            }
+
+            fn decode_with(
+                bytes: &mut ::bytes::Bytes,
+                config: &$crate::DecodeConfig,
+            ) -> $crate::Result<Self> {
+                $crate::tlv::decode_record(bytes, config, Some(Self::TYP), |_, value| {
+                    if !value.is_empty() {
+                        return Err($crate::TlvError::UnexpectedLength.at(0));
+                    }
+                    Ok(Self)
+                })
+                .map_err(|e| e.in_tlv(Some(stringify!($ty)), Self::TYP))
+            }
+        }
+    };
+}
+pub(crate) use empty_record;
+
 /// A generic TLV record whose type is only known at runtime
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GenericTlv<T> {
@@ -90,18 +242,45 @@ where
     T: TlvDecode,
 {
     fn decode(bytes: &mut Bytes) -> crate::Result<Self> {
-        let typ = VarNum::decode(bytes)?.into();
-        let len = VarNum::decode(bytes)?;
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> crate::Result<Self> {
+        decode_record(bytes, config, None, |typ, mut value| {
+            let len = VarNum::from(value.len());
+            let content = config
+                .nested()
+                .and_then(|config| T::decode_with(&mut value, &config))
+                .map_err(|e| e.in_tlv(None, typ.into()))?;
+            Ok(Self { typ, len, content })
+        })
+    }
+}
+
+impl<'a, T> TlvDecodeRef<'a> for GenericTlv<T>
+where
+    T: TlvDecodeRef<'a>,
+{
+    fn decode_ref(bytes: &mut &'a [u8]) -> crate::Result<Self> {
+        Self::decode_ref_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_ref_with(bytes: &mut &'a [u8], config: &DecodeConfig) -> crate::Result<Self> {
+        config.check_total_length(bytes.len())?;
+        let typ = VarNum::decode_ref_with(bytes, config)?;
+        let len = VarNum::decode_ref_with(bytes, config)?;
+        config.check_tlv_length(len.value())?;
 
         if bytes.remaining() < len.into() {
             return Err(TlvError::UnexpectedEndOfStream);
         }
 
-        let mut inner_data = bytes.split_to(len.into());
+        let (mut inner_data, rest) = bytes.split_at(len.into());
+        *bytes = rest;
         Ok(Self {
             typ,
             len,
-            content: T::decode(&mut inner_data)?,
+            content: T::decode_ref_with(&mut inner_data, &config.nested()?)?,
         })
     }
 }
@@ -111,17 +290,23 @@ where
     T: TlvEncode,
 {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
-        bytes.put(self.typ.encode());
-        bytes.put(self.len.encode());
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        self.typ.encode_to(buf);
+        self.len.encode_to(buf);
 
-        let mut content = self.content.encode();
-        content.truncate(self.len.into());
-        if content.len() != self.len.into() {
+        let len = usize::from(self.len);
+        let content_size = self.content.size();
+        if content_size < len {
             panic!("GenericTLV length longer than encoded content");
         }
-        bytes.put(content);
-        bytes.freeze()
+        if content_size == len {
+            self.content.encode_to(buf);
+        } else {
+            buf.put_slice(&self.content.encode()[..len]);
+        }
     }
 
     fn size(&self) -> usize {
@@ -131,10 +316,16 @@ where
 
 #[cfg(test)]
 mod tests {
-    use bytes::{Buf, BufMut, Bytes, BytesMut};
+    use alloc::string::ToString;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use bytes::{Buf, BufMut, Bytes};
 
     use crate::tests::GenericNameComponent;
-    use crate::{error::TlvError, Result, TlvDecode, TlvEncode, VarNum};
+    use crate::{
+        error::TlvError, NonNegativeInteger, PathSegment, Result, Tlv, TlvDecode, TlvEncode, VarNum,
+    };
 
     use super::*;
 
@@ -152,17 +343,36 @@ mod tests {
     }
 
     impl TlvDecode for Name {
-        fn decode(mut bytes: &mut Bytes) -> Result<Self> {
-            let typ = VarNum::decode(&mut bytes)?;
+        fn decode(bytes: &mut Bytes) -> Result<Self> {
+            Self::decode_with(bytes, &DecodeConfig::default())
+        }
+
+        fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+            let start_len = bytes.len();
+            let typ = VarNum::decode_with(bytes, config)?;
             if usize::from(typ) != Self::TYP {
                 return Err(TlvError::TypeMismatch {
                     expected: Self::TYP,
                     found: typ.into(),
                 });
             }
-            let length = VarNum::decode(&mut bytes)?;
-            let mut inner_data = bytes.copy_to_bytes(length.into());
-            let components = Vec::<GenericNameComponent>::decode(&mut inner_data)?;
+            let length = VarNum::decode_with(bytes, config)?;
+            config.check_tlv_length(length.value())?;
+            let inner_start = start_len - bytes.len();
+            // A copy, so that positions in it are unrelated to those in `bytes`
+            let mut inner_data = Bytes::copy_from_slice(&bytes[..length.into()]);
+            bytes.advance(length.into());
+            let components = config
+                .nested()
+                .and_then(|config| {
+                    Vec::<GenericNameComponent>::decode_with(&mut inner_data, &config)
+                })
+                .map_err(|e| {
+                    e.within(inner_start, length.into())
+                        .in_field("components")
+                        .in_tlv(Some("Name"), Self::TYP)
+                        .relative_to(start_len)
+                })?;
 
             Ok(Self { components })
         }
@@ -170,12 +380,13 @@ mod tests {
 
     impl TlvEncode for Name {
         fn encode(&self) -> Bytes {
-            let mut bytes = BytesMut::with_capacity(self.size());
-            bytes.put(VarNum::from(Self::TYP).encode());
-            bytes.put(VarNum::from(self.inner_size()).encode());
-            bytes.put(self.components.encode());
+            encode_to_bytes(self)
+        }
 
-            bytes.freeze()
+        fn encode_to(&self, buf: &mut impl BufMut) {
+            VarNum::from(Self::TYP).encode_to(buf);
+            VarNum::from(self.inner_size()).encode_to(buf);
+            self.components.encode_to(buf);
         }
 
         fn size(&self) -> usize {
@@ -217,4 +428,225 @@ mod tests {
         assert_eq!(name.components[0].name, &b"hello"[..]);
         assert_eq!(name.components[1].name, &b"world"[..]);
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_reader() {
+        let data = [8, 5, b'h', b'e', b'l', b'l', b'o', 33, 0];
+        let mut cursor = std::io::Cursor::new(&data[..]);
+
+        let component = GenericNameComponent::from_reader(&mut cursor).unwrap();
+        assert_eq!(component.name, &b"hello"[..]);
+        assert_eq!(cursor.position(), 7);
+        assert_eq!(
+            GenericNameComponent::from_reader(&mut cursor).unwrap_err(),
+            TlvError::TypeMismatch {
+                expected: 8,
+                found: 33
+            }
+        );
+    }
+
+    #[test]
+    fn encode_to() {
+        let name = Name {
+            components: vec![
+                GenericNameComponent {
+                    name: Bytes::from_static(b"hello"),
+                },
+                GenericNameComponent {
+                    name: Bytes::from_static(b"world"),
+                },
+            ],
+        };
+
+        let mut buf = vec![0xAA];
+        name.encode_to(&mut buf);
+        assert_eq!(
+            buf,
+            &[0xAA, 7, 14, 8, 5, b'h', b'e', b'l', b'l', b'o', 8, 5, b'w', b'o', b'r', b'l', b'd']
+        );
+        assert_eq!(name.encode(), &buf[1..]);
+        assert_eq!(name.size(), buf.len() - 1);
+    }
+
+    #[test]
+    fn generic_tlv_truncated() {
+        let tlv = GenericTlv {
+            typ: VarNum::from(8u8),
+            len: VarNum::from(2u8),
+            content: Bytes::from_static(b"hello"),
+        };
+
+        let mut buf = Vec::new();
+        tlv.encode_to(&mut buf);
+        assert_eq!(buf, &[8, 2, b'h', b'e']);
+    }
+
+    #[test]
+    fn generic_tlv_strict() {
+        let strict = DecodeConfig::new().with_strict(true);
+        let data = Bytes::from_static(&[8, 0xFD, 0x00, 0x02, 0x01, 0x02]);
+
+        let tlv = GenericTlv::<Bytes>::decode(&mut data.clone()).unwrap();
+        assert_eq!(tlv.content, &[1, 2][..]);
+        assert_eq!(
+            GenericTlv::<Bytes>::decode_with(&mut data.clone(), &strict).unwrap_err(),
+            TlvError::NonMinimalEncoding
+        );
+
+        let data = Bytes::from_static(&[25, 2, 0x00, 0x10]);
+        let tlv = GenericTlv::<NonNegativeInteger>::decode(&mut data.clone()).unwrap();
+        assert_eq!(tlv.content, NonNegativeInteger::U16(0x10));
+        assert_eq!(
+            GenericTlv::<NonNegativeInteger>::decode_with(&mut data.clone(), &strict).unwrap_err(),
+            TlvError::NonMinimalEncoding
+        );
+    }
+
+    #[test]
+    fn error_context() {
+        let mut data = Bytes::from_static(&[6, 7, 20, 5, 25, 3, 1, 2, 3]);
+        let error = GenericTlv::<GenericTlv<GenericTlv<NonNegativeInteger>>>::decode(&mut data)
+            .unwrap_err();
+
+        assert_eq!(error, TlvError::UnexpectedLength);
+        assert_eq!(error.kind(), &TlvError::UnexpectedLength);
+        let context = error.context().unwrap();
+        assert_eq!(context.offset, Some(6));
+        assert_eq!(
+            context.path,
+            [
+                PathSegment::Tlv { name: None, typ: 6 },
+                PathSegment::Tlv {
+                    name: None,
+                    typ: 20
+                },
+                PathSegment::Tlv {
+                    name: None,
+                    typ: 25
+                },
+            ]
+        );
+        assert_eq!(
+            error.to_string(),
+            "TLV Record had unexpected length at byte 6 in TLV(6) > TLV(20) > TLV(25)"
+        );
+    }
+
+    #[test]
+    fn error_context_vec() {
+        // Second component is truncated
+        let mut data =
+            Bytes::from_static(&[130, 11, 7, 9, 8, 2, b'a', b'b', 8, 0xFD, 0x00, 0x02, b'c']);
+        let error = GenericTlv::<Name>::decode(&mut data).unwrap_err();
+
+        assert_eq!(error, TlvError::UnexpectedEndOfStream);
+        assert_eq!(
+            error.to_string(),
+            "Unexpected end of stream at byte 8 in TLV(130) > Name(7) > components"
+        );
+    }
+
+    #[test]
+    fn error_context_derived() {
+        #[derive(Debug, Tlv)]
+        #[tlv(130, internal = true)]
+        struct Derived {
+            inner: GenericTlv<NonNegativeInteger>,
+        }
+
+        // The derived decoder does not record positions, so the error is reported at its start
+        let mut data = Bytes::from_static(&[6, 7, 130, 5, 25, 3, 1, 2, 3]);
+        let error = GenericTlv::<Derived>::decode(&mut data).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "TLV Record had unexpected length at byte 2 in TLV(6) > TLV(25)"
+        );
+    }
+
+    #[test]
+    fn error_context_header() {
+        let mut data = Bytes::from_static(&[8, 0xFE, 0x00]);
+        let error = GenericTlv::<Bytes>::decode(&mut data).unwrap_err();
+
+        assert_eq!(error.to_string(), "Unexpected end of stream at byte 1");
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Value(NonNegativeInteger);
+
+    value_record!(Value, 130);
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Empty;
+
+    empty_record!(Empty, 131);
+
+    #[test]
+    fn value_records() {
+        let value = Value(NonNegativeInteger::new(0x0102));
+        let mut buf = Vec::new();
+        value.encode_to(&mut buf);
+        Empty.encode_to(&mut buf);
+        assert_eq!(buf, [130, 2, 1, 2, 131, 0]);
+        assert_eq!(value.encode(), &buf[..4]);
+        assert_eq!(value.size(), 4);
+
+        let mut data = Bytes::from(buf);
+        assert_eq!(Value::decode(&mut data).unwrap(), value);
+        assert_eq!(Empty::decode(&mut data).unwrap(), Empty);
+        assert!(data.is_empty());
+
+        let strict = DecodeConfig::new().with_strict(true);
+        let mut data = Bytes::from_static(&[130, 2, 0, 1]);
+        assert_eq!(
+            Value::decode_with(&mut data, &strict).unwrap_err(),
+            TlvError::NonMinimalEncoding
+        );
+
+        let error = Empty::decode(&mut Bytes::from_static(&[131, 1, 0])).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "TLV Record had unexpected length at byte 2 in Empty(131)"
+        );
+        // Anything after the value is rejected
+        let error = crate::Nonce::decode(&mut Bytes::from_static(&[10, 5, 1, 2, 3, 4, 5]));
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "TLV Record had unexpected length at byte 6 in Nonce(10)"
+        );
+    }
+
+    #[test]
+    fn generic_tlv_ref() {
+        let data = [
+            7, 14, 8, 5, b'h', b'e', b'l', b'l', b'o', 8, 5, b'w', b'o', b'r', b'l', b'd', 255,
+            255, 255,
+        ];
+        let mut slice = &data[..];
+        let name = GenericTlv::<&[u8]>::decode_ref(&mut slice).unwrap();
+
+        assert_eq!(slice.len(), 3);
+        assert_eq!(name.typ.value(), 7);
+        assert_eq!(name.len.value(), 14);
+
+        let mut content = name.content;
+        let components = Vec::<GenericTlv<&[u8]>>::decode_ref(&mut content).unwrap();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].content, b"hello");
+        assert_eq!(components[1].content, b"world");
+        // Borrowed straight out of `data`
+        assert_eq!(components[1].content.as_ptr(), data[11..].as_ptr());
+
+        assert_eq!(name.encode(), &data[..16]);
+    }
+
+    #[test]
+    fn generic_tlv_ref_overlength() {
+        let data = [8, 6, b'h', b'e', b'l', b'l', b'o'];
+        let component = GenericTlv::<&[u8]>::decode_ref(&mut &data[..]);
+        assert_eq!(component.unwrap_err(), TlvError::UnexpectedEndOfStream);
+    }
 }
@@ -0,0 +1,274 @@
+use alloc::vec::Vec;
+
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::tlv::split_record;
+use crate::{
+    encode_to_bytes, tlv_typ_critical, DecodeConfig, GenericTlv, Result, TlvDecode, TlvEncode,
+    VarNum,
+};
+
+/// An unknown non-critical TLV record that was skipped while decoding
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownTlv {
+    /// Number of known fields that preceded the record
+    pub position: usize,
+    /// The TLV-TYPE of the record
+    pub typ: usize,
+    /// The whole record as it was found, including its TLV-TYPE and TLV-LENGTH
+    pub record: Bytes,
+}
+
+/// Unknown non-critical TLV records within a record, preserved for re-encoding
+///
+/// Decoders usually skip unknown non-critical records, so re-encoding a decoded value drops them.
+/// Collecting them here instead, along with their position among the known fields, allows the
+/// value to be re-encoded exactly as it was received, which keeps signatures over it valid.
+///
+/// When decoding, call [`collect`](UnknownTlvs::collect) before each known field and once more
+/// after the last one. When encoding, call [`encode_at`](UnknownTlvs::encode_at) in the same
+/// places. Records are kept as they were found, so they are reproduced byte for byte even if their
+/// TLV-TYPE or TLV-LENGTH was not minimally encoded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct UnknownTlvs {
+    records: Vec<UnknownTlv>,
+}
+
+impl UnknownTlvs {
+    /// Create an empty collection
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `tlv` as if it was found before the known field number `position`
+    pub fn push(&mut self, position: usize, tlv: GenericTlv<Bytes>) {
+        let typ = tlv.typ.into();
+        self.push_record(position, typ, tlv.encode());
+    }
+
+    /// Add the encoded `record` of type `typ`, found before the known field number `position`
+    pub(crate) fn push_record(&mut self, position: usize, typ: usize, record: Bytes) {
+        self.records.push(UnknownTlv {
+            position,
+            typ,
+            record,
+        });
+    }
+
+    /// The collected records, in the order they were found
+    pub fn iter(&self) -> core::slice::Iter<'_, UnknownTlv> {
+        self.records.iter()
+    }
+
+    /// Number of collected records
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether no records have been collected
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Collect unknown non-critical records from the start of `bytes`
+    ///
+    /// Records are taken from `bytes` for as long as they are non-critical and their type is not
+    /// in `known`. `position` is the number of known fields decoded so far.
+    pub fn collect(
+        &mut self,
+        bytes: &mut Bytes,
+        config: &DecodeConfig,
+        position: usize,
+        known: &[usize],
+    ) -> Result<()> {
+        while bytes.has_remaining() {
+            let typ = usize::from(VarNum::decode_with(&mut bytes.clone(), config)?);
+            if tlv_typ_critical(typ) || known.contains(&typ) {
+                break;
+            }
+            let mut record = bytes.clone();
+            split_record(bytes, config)?;
+            record.truncate(record.len() - bytes.len());
+            self.push_record(position, typ, record);
+        }
+        Ok(())
+    }
+
+    /// Encode the records found before the known field number `position`
+    pub fn encode_at(&self, position: usize, buf: &mut impl BufMut) {
+        for record in self.records.iter().filter(|r| r.position == position) {
+            buf.put_slice(&record.record);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a UnknownTlvs {
+    type Item = &'a UnknownTlv;
+    type IntoIter = core::slice::Iter<'a, UnknownTlv>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Encodes all records in the order they were found
+impl TlvEncode for UnknownTlvs {
+    fn encode(&self) -> Bytes {
+        encode_to_bytes(self)
+    }
+
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        for record in &self.records {
+            buf.put_slice(&record.record);
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.records.iter().map(|r| r.record.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{FaceId, GenericNameComponent};
+    use crate::{NonNegativeInteger, Tlv, TlvError};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Record {
+        name: GenericNameComponent,
+        face_id: Option<FaceId>,
+        extra: UnknownTlvs,
+    }
+
+    const KNOWN: &[usize] = &[GenericNameComponent::TYP, FaceId::TYP];
+
+    impl Tlv for Record {
+        const TYP: usize = 104;
+
+        fn inner_size(&self) -> usize {
+            self.name.size() + self.face_id.size() + self.extra.size()
+        }
+    }
+
+    impl TlvDecode for Record {
+        fn decode(bytes: &mut Bytes) -> Result<Self> {
+            Self::decode_with(bytes, &DecodeConfig::default())
+        }
+
+        fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+            let typ = VarNum::decode_with(bytes, config)?;
+            if usize::from(typ) != Self::TYP {
+                return Err(TlvError::TypeMismatch {
+                    expected: Self::TYP,
+                    found: typ.into(),
+                });
+            }
+            let length = VarNum::decode_with(bytes, config)?;
+            if bytes.remaining() < length.into() {
+                return Err(TlvError::UnexpectedEndOfStream);
+            }
+            let mut inner_data = bytes.split_to(length.into());
+            let config = config.nested()?;
+
+            let mut extra = UnknownTlvs::new();
+            extra.collect(&mut inner_data, &config, 0, KNOWN)?;
+            let name = GenericNameComponent::decode_with(&mut inner_data, &config)?;
+            extra.collect(&mut inner_data, &config, 1, KNOWN)?;
+            let face_id = Option::<FaceId>::decode_with(&mut inner_data, &config)?;
+            extra.collect(&mut inner_data, &config, 2, KNOWN)?;
+
+            Ok(Self {
+                name,
+                face_id,
+                extra,
+            })
+        }
+    }
+
+    impl TlvEncode for Record {
+        fn encode(&self) -> Bytes {
+            encode_to_bytes(self)
+        }
+
+        fn encode_to(&self, buf: &mut impl BufMut) {
+            VarNum::from(Self::TYP).encode_to(buf);
+            VarNum::from(self.inner_size()).encode_to(buf);
+            self.extra.encode_at(0, buf);
+            self.name.encode_to(buf);
+            self.extra.encode_at(1, buf);
+            self.face_id.encode_to(buf);
+            self.extra.encode_at(2, buf);
+        }
+
+        fn size(&self) -> usize {
+            VarNum::from(Self::TYP).size()
+                + VarNum::from(self.inner_size()).size()
+                + self.inner_size()
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let data = Bytes::from_static(&[
+            104, 15, 200, 1, 1, 8, 1, b'a', 202, 2, 2, 3, 105, 1, 5, 204, 0,
+        ]);
+        let record = Record::decode(&mut data.clone()).unwrap();
+        assert_eq!(record.name.name, &b"a"[..]);
+        assert_eq!(
+            record.face_id,
+            Some(FaceId {
+                id: NonNegativeInteger::U8(5)
+            })
+        );
+        assert_eq!(record.extra.len(), 3);
+        let positions: Vec<_> = record.extra.iter().map(|r| (r.position, r.typ)).collect();
+        assert_eq!(positions, [(0, 200), (1, 202), (2, 204)]);
+        assert_eq!(record.encode(), data);
+    }
+
+    #[test]
+    fn optional_field_absent() {
+        let data = Bytes::from_static(&[104, 6, 8, 1, b'a', 202, 1, 2]);
+        let record = Record::decode(&mut data.clone()).unwrap();
+        assert_eq!(record.face_id, None);
+        assert_eq!(record.extra.iter().next().unwrap().position, 1);
+        assert_eq!(record.encode(), data);
+    }
+
+    #[test]
+    fn non_minimal_header() {
+        // A TLV-LENGTH of 2 encoded in three bytes
+        let data = Bytes::from_static(&[104, 9, 8, 1, b'a', 202, 0xFD, 0, 2, 2, 3]);
+        let record = Record::decode(&mut data.clone()).unwrap();
+        let unknown = record.extra.iter().next().unwrap();
+        assert_eq!(unknown.typ, 202);
+        assert_eq!(unknown.record, &[202, 0xFD, 0, 2, 2, 3][..]);
+        assert_eq!(record.size(), data.len());
+        assert_eq!(record.encode(), data);
+    }
+
+    #[test]
+    fn stops_at_critical() {
+        let mut data = Bytes::from_static(&[200, 0, 9, 0, 202, 0]);
+        let mut extra = UnknownTlvs::new();
+        extra
+            .collect(&mut data, &DecodeConfig::default(), 0, &[])
+            .unwrap();
+        assert_eq!(extra.len(), 1);
+        assert_eq!(&data[..], &[9, 0, 202, 0]);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut data = Bytes::from_static(&[200, 3, 1]);
+        let mut extra = UnknownTlvs::new();
+        assert_eq!(
+            extra
+                .collect(&mut data, &DecodeConfig::default(), 0, &[])
+                .unwrap_err(),
+            TlvError::UnexpectedEndOfStream
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use bytes::{Buf, Bytes};
+
+use crate::tlv::split_record;
+use crate::{tlv_typ_critical, DecodeConfig, Result, TlvError};
+
+/// Decode the TLV records in `bytes` in any order
+///
+/// This is used for set-like records whose sub-TLVs may appear in any order. For each record,
+/// `field` is called with the TLV-TYPE and the entire record, including type and length. It should
+/// decode the record into the matching field and return `true`, or return `false` if the type is
+/// unknown. Unknown non-critical records are skipped, while unknown critical records lead to
+/// [`TlvError::UnknownCritical`].
+///
+/// Use [`set_unordered_field`] to detect duplicates and [`required_field`] to detect missing
+/// fields. All of `bytes` is consumed.
+pub fn decode_unordered(
+    bytes: &mut Bytes,
+    config: &DecodeConfig,
+    mut field: impl FnMut(usize, &mut Bytes) -> Result<bool>,
+) -> Result<()> {
+    let len = bytes.len();
+    while bytes.has_remaining() {
+        let remaining = bytes.len();
+        let mut decode_record = || {
+            let mut record = bytes.clone();
+            let (typ, _) = split_record(bytes, config)?;
+            record.truncate(record.len() - bytes.len());
+            let (typ, record_len) = (usize::from(typ), record.len());
+            if !field(typ, &mut record).map_err(|e| e.within(0, record_len))?
+                && tlv_typ_critical(typ)
+            {
+                return Err(TlvError::UnknownCritical { typ });
+            }
+            Ok(())
+        };
+        decode_record().map_err(|e| e.within(len - remaining, remaining).relative_to(len))?;
+    }
+    Ok(())
+}
+
+/// Store the decoded field of type `typ` in `slot`
+///
+/// Fails with [`TlvError::DuplicateField`] if `slot` has already been filled.
+pub fn set_unordered_field<T>(slot: &mut Option<T>, typ: usize, value: T) -> Result<()> {
+    if slot.is_some() {
+        return Err(TlvError::DuplicateField { typ });
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Take the value of the required field of type `typ` out of `slot`
+///
+/// Fails with [`TlvError::MissingField`] if `slot` was never filled.
+pub fn required_field<T>(slot: Option<T>, typ: usize) -> Result<T> {
+    slot.ok_or(TlvError::MissingField { typ })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+
+    use crate::tests::{FaceId, GenericNameComponent};
+    use crate::tlv::decode_record;
+    use crate::{encode_to_bytes, NonNegativeInteger, Tlv, TlvDecode, TlvEncode, VarNum};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq, Tlv)]
+    #[tlv(106, internal = true)]
+    struct Cost {
+        cost: NonNegativeInteger,
+    }
+
+    /// A set-like record whose fields may appear in any order
+    #[derive(Debug, PartialEq, Eq)]
+    struct ControlParameters {
+        name: GenericNameComponent,
+        face_id: Option<FaceId>,
+        cost: Option<Cost>,
+    }
+
+    impl Tlv for ControlParameters {
+        const TYP: usize = 104;
+
+        fn inner_size(&self) -> usize {
+            self.name.size() + self.face_id.size() + self.cost.size()
+        }
+    }
+
+    impl TlvDecode for ControlParameters {
+        fn decode(bytes: &mut Bytes) -> Result<Self> {
+            Self::decode_with(bytes, &DecodeConfig::default())
+        }
+
+        fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+            decode_record(bytes, config, Some(Self::TYP), |_, mut inner_data| {
+                let (mut name, mut face_id, mut cost) = (None, None, None);
+                let config = config.nested()?;
+                decode_unordered(&mut inner_data, &config, |typ, record| {
+                    match typ {
+                        GenericNameComponent::TYP => set_unordered_field(
+                            &mut name,
+                            typ,
+                            GenericNameComponent::decode_with(record, &config)?,
+                        )?,
+                        FaceId::TYP => set_unordered_field(
+                            &mut face_id,
+                            typ,
+                            FaceId::decode_with(record, &config)?,
+                        )?,
+                        Cost::TYP => set_unordered_field(
+                            &mut cost,
+                            typ,
+                            Cost::decode_with(record, &config)?,
+                        )?,
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                Ok(Self {
+                    name: required_field(name, GenericNameComponent::TYP)?,
+                    face_id,
+                    cost,
+                })
+            })
+            .map_err(|e| e.in_tlv(Some("ControlParameters"), Self::TYP))
+        }
+    }
+
+    impl TlvEncode for ControlParameters {
+        fn encode(&self) -> Bytes {
+            encode_to_bytes(self)
+        }
+
+        fn encode_to(&self, buf: &mut impl BufMut) {
+            VarNum::from(Self::TYP).encode_to(buf);
+            VarNum::from(self.inner_size()).encode_to(buf);
+            self.name.encode_to(buf);
+            self.face_id.encode_to(buf);
+            self.cost.encode_to(buf);
+        }
+
+        fn size(&self) -> usize {
+            VarNum::from(Self::TYP).size()
+                + VarNum::from(self.inner_size()).size()
+                + self.inner_size()
+        }
+    }
+
+    fn decode(data: &'static [u8]) -> Result<ControlParameters> {
+        ControlParameters::decode(&mut Bytes::from_static(data))
+    }
+
+    #[test]
+    fn any_order() {
+        let expected = ControlParameters {
+            name: GenericNameComponent {
+                name: Bytes::from_static(b"a"),
+            },
+            face_id: Some(FaceId {
+                id: NonNegativeInteger::U8(5),
+            }),
+            cost: Some(Cost {
+                cost: NonNegativeInteger::U8(10),
+            }),
+        };
+        assert_eq!(
+            decode(&[104, 9, 8, 1, b'a', 105, 1, 5, 106, 1, 10]).unwrap(),
+            expected
+        );
+        assert_eq!(
+            decode(&[104, 9, 106, 1, 10, 105, 1, 5, 8, 1, b'a']).unwrap(),
+            expected
+        );
+        assert_eq!(
+            ControlParameters::decode(&mut expected.encode()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn optional_missing() {
+        let decoded = decode(&[104, 6, 106, 1, 10, 8, 1, b'a']).unwrap();
+        assert_eq!(decoded.face_id, None);
+        assert_eq!(
+            decoded.cost,
+            Some(Cost {
+                cost: NonNegativeInteger::U8(10)
+            })
+        );
+    }
+
+    #[test]
+    fn duplicate() {
+        let error = decode(&[104, 9, 105, 1, 5, 8, 1, b'a', 105, 1, 6]).unwrap_err();
+        assert_eq!(error, TlvError::DuplicateField { typ: 105 });
+        assert_eq!(error.context().unwrap().offset, Some(8));
+    }
+
+    #[test]
+    fn required_missing() {
+        assert_eq!(
+            decode(&[104, 3, 105, 1, 5]).unwrap_err(),
+            TlvError::MissingField { typ: 8 }
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        // Non-critical records are skipped
+        let decoded = decode(&[104, 7, 200, 2, 1, 2, 8, 1, b'a']).unwrap();
+        assert_eq!(decoded.name.name, &b"a"[..]);
+
+        // Critical records are rejected
+        let error = decode(&[104, 7, 8, 1, b'a', 201, 2, 1, 2]).unwrap_err();
+        assert_eq!(error, TlvError::UnknownCritical { typ: 201 });
+        assert_eq!(error.context().unwrap().offset, Some(5));
+    }
+
+    #[test]
+    fn truncated_record() {
+        assert_eq!(
+            decode(&[104, 4, 8, 1, b'a', 105]).unwrap_err(),
+            TlvError::UnexpectedEndOfStream
+        );
+        assert_eq!(
+            decode(&[104, 5, 8, 1, b'a', 105, 3]).unwrap_err(),
+            TlvError::UnexpectedEndOfStream
+        );
+    }
+}
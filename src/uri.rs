@@ -0,0 +1,264 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+use crate::{
+    hex, ComponentType, Name, NameComponent, NonNegativeInteger, TlvDecode, TlvEncode, TlvError,
+};
+
+/// Prefixes of the alternate URI representations of typed name components
+const DIGEST_PREFIXES: [(&str, ComponentType); 2] = [
+    ("sha256digest", ComponentType::ImplicitSha256Digest),
+    ("params-sha256", ComponentType::ParametersSha256Digest),
+];
+const NUMBER_PREFIXES: [(&str, ComponentType); 5] = [
+    ("seg", ComponentType::Segment),
+    ("off", ComponentType::ByteOffset),
+    ("v", ComponentType::Version),
+    ("t", ComponentType::Timestamp),
+    ("seq", ComponentType::SequenceNum),
+];
+
+fn invalid(reason: &'static str) -> TlvError {
+    TlvError::InvalidUri { reason }
+}
+
+/// Write `value` percent-encoded, prefixing values made up only of periods with `...`
+fn write_escaped(f: &mut fmt::Formatter<'_>, value: &[u8]) -> fmt::Result {
+    if value.iter().all(|&byte| byte == b'.') {
+        f.write_str("...")?;
+    }
+    for &byte in value {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            f.write_char(byte as char)?;
+        } else {
+            write!(f, "%{byte:02X}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Reverse of [`write_escaped`]
+fn unescape(escaped: &str) -> crate::Result<Vec<u8>> {
+    if escaped.is_empty() {
+        return Err(invalid("empty name component"));
+    }
+    let mut value = Vec::with_capacity(escaped.len());
+    let mut bytes = escaped.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte != b'%' {
+            value.push(byte);
+            continue;
+        }
+        let mut digit = || {
+            bytes
+                .next()
+                .and_then(|digit| (digit as char).to_digit(16))
+                .ok_or(invalid("invalid percent-encoding"))
+        };
+        value.push((digit()? << 4 | digit()?) as u8);
+    }
+
+    if value.iter().all(|&byte| byte == b'.') {
+        if value.len() < 3 {
+            return Err(invalid("name component cannot be . or .."));
+        }
+        value.drain(..3);
+    }
+    Ok(value)
+}
+
+/// Uses the NDN URI scheme, with the alternate representations `sha256digest=`, `params-sha256=`,
+/// `seg=`, `off=`, `v=`, `t=` and `seq=` for typed components
+///
+/// Numbered components only use their alternate representation if the number is minimally encoded,
+/// so that parsing the URI results in the same encoding.
+impl fmt::Display for NameComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let typ = self.component_type();
+        if let Some((prefix, _)) = DIGEST_PREFIXES.iter().find(|(_, t)| *t == typ) {
+            return write!(f, "{prefix}={}", hex::encode(self.value()));
+        }
+        if let Some((prefix, _)) = NUMBER_PREFIXES.iter().find(|(_, t)| *t == typ) {
+            if let Ok(number) = NonNegativeInteger::decode(&mut self.value().clone()) {
+                if number.is_minimal() {
+                    return write!(f, "{prefix}={number}");
+                }
+            }
+        }
+        if typ != ComponentType::Generic {
+            write!(f, "{}=", self.typ())?;
+        }
+        write_escaped(f, self.value())
+    }
+}
+
+impl FromStr for NameComponent {
+    type Err = TlvError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let Some((prefix, value)) = s.split_once('=') else {
+            return Ok(NameComponent::generic(unescape(s)?));
+        };
+
+        if !prefix.is_empty() && prefix.bytes().all(|b| b.is_ascii_digit()) {
+            let typ = prefix
+                .parse::<usize>()
+                .ok()
+                .filter(|typ| (1..=0xFFFF).contains(typ))
+                .ok_or(invalid("invalid name component type"))?;
+            return NameComponent::new(typ, unescape(value)?)
+                .map_err(|_| invalid("invalid digest length"));
+        }
+        if let Some((_, typ)) = DIGEST_PREFIXES.iter().find(|(p, _)| *p == prefix) {
+            let digest = Some(value)
+                .filter(|value| value.len() == 64)
+                .and_then(|value| hex::decode(value).ok())
+                .ok_or(invalid("invalid digest"))?;
+            return NameComponent::new(typ.typ(), digest)
+                .map_err(|_| invalid("invalid digest length"));
+        }
+        if let Some((_, typ)) = NUMBER_PREFIXES.iter().find(|(p, _)| *p == prefix) {
+            // `u64::from_str` accepts a leading `+`
+            let number = Some(value)
+                .filter(|value| !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|value| value.parse::<u64>().ok())
+                .ok_or(invalid("invalid number"))?;
+            return NameComponent::new(typ.typ(), NonNegativeInteger::new(number).encode());
+        }
+        Err(invalid("unknown name component type"))
+    }
+}
+
+/// Uses the NDN URI scheme, see [`NameComponent`] for the representation of each component
+///
+/// The name without components is written as `/`.
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("/");
+        }
+        for component in self {
+            write!(f, "/{component}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses an NDN URI
+///
+/// The `ndn:` scheme and an authority are ignored if present, as is a trailing `/`. Empty
+/// components, for example in `/a//b`, are rejected.
+impl FromStr for Name {
+    type Err = TlvError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let mut path = s.strip_prefix("ndn:").unwrap_or(s);
+        if let Some(authority) = path.strip_prefix("//") {
+            path = authority.find('/').map_or("", |start| &authority[start..]);
+        }
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let path = path.strip_suffix('/').unwrap_or(path);
+        if path.is_empty() {
+            return Ok(Name::new());
+        }
+        path.split('/').map(NameComponent::from_str).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use bytes::Bytes;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let digest = "28bad4b5275bd392dbb670c75cf0b66f13f7942b21e80f55c0e86b374753a548";
+        let uris = [
+            "/",
+            "/hello/world",
+            "/a%2Fb/%00%FF/-._~",
+            "/.../..../.....",
+            "/32=key/200=%01%02/65535=...",
+            "/seg=3/off=4/v=1700000000/t=0/seq=18446744073709551615",
+            "/50=%00%03/54=%01%02%03",
+        ];
+        for uri in uris {
+            let name = uri.parse::<Name>().unwrap();
+            assert_eq!(name.to_string(), uri);
+            assert_eq!(Name::decode(&mut name.encode()).unwrap(), name);
+        }
+
+        let uri = alloc::format!("/sha256digest={digest}/params-sha256={digest}");
+        let name = uri.parse::<Name>().unwrap();
+        assert_eq!(name.to_string(), uri);
+        assert_eq!(
+            name.get(0).unwrap().component_type(),
+            ComponentType::ImplicitSha256Digest
+        );
+        assert_eq!(
+            name.get(1).unwrap().component_type(),
+            ComponentType::ParametersSha256Digest
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let name = "/hello/seg=3/.../32=key".parse::<Name>().unwrap();
+        let expected = Name::from_iter([
+            NameComponent::generic(&b"hello"[..]),
+            NameComponent::segment(3),
+            NameComponent::generic(Bytes::new()),
+            NameComponent::keyword(&b"key"[..]),
+        ]);
+        assert_eq!(name, expected);
+        assert_eq!(name.encode(), expected.encode());
+
+        let cases = [
+            ("ndn:/a/b", "/a/b"),
+            ("ndn://authority/a", "/a"),
+            ("/a/b/", "/a/b"),
+            ("a/b", "/a/b"),
+            ("ndn:", "/"),
+            ("/8=a%2fb", "/a%2Fb"),
+            ("/a+b c", "/a%2Bb%20c"),
+            ("/50=%03", "/seg=3"),
+        ];
+        for (uri, expected) in cases {
+            assert_eq!(uri.parse::<Name>().unwrap().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let cases = [
+            ("/a//b", "empty name component"),
+            ("/.", "name component cannot be . or .."),
+            ("/..", "name component cannot be . or .."),
+            ("/%zz", "invalid percent-encoding"),
+            ("/%4", "invalid percent-encoding"),
+            ("/foo=bar", "unknown name component type"),
+            ("/0=a", "invalid name component type"),
+            ("/65536=a", "invalid name component type"),
+            ("/1=abc", "invalid digest length"),
+            ("/sha256digest=00", "invalid digest"),
+            ("/seg=", "invalid number"),
+            ("/seg=+1", "invalid number"),
+            ("/v=18446744073709551616", "invalid number"),
+        ];
+        for (uri, reason) in cases {
+            assert_eq!(
+                uri.parse::<Name>().unwrap_err(),
+                TlvError::InvalidUri { reason },
+                "{uri}"
+            );
+        }
+        assert_eq!(
+            "/%zz".parse::<Name>().unwrap_err().to_string(),
+            "Invalid NDN URI: invalid percent-encoding"
+        );
+    }
+}
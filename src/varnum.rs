@@ -1,7 +1,9 @@
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{Buf, BufMut, Bytes};
 use derive_more::{AsMut, AsRef, Display};
 
-use crate::{error::TlvError, Result, TlvDecode, TlvEncode};
+use crate::{
+    encode_to_bytes, error::TlvError, DecodeConfig, Result, TlvDecode, TlvDecodeRef, TlvEncode,
+};
 
 /// A variable-length number as used by TLV encoded values
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Display, AsRef, AsMut)]
@@ -19,6 +21,16 @@ impl VarNum {
     pub fn value(&self) -> u64 {
         self.value
     }
+
+    /// The size of an encoded `VarNum` starting with the byte `first`
+    pub(crate) const fn encoded_size(first: u8) -> usize {
+        match first {
+            0xFD => 3,
+            0xFE => 5,
+            0xFF => 9,
+            _ => 1,
+        }
+    }
 }
 
 impl From<usize> for VarNum {
@@ -143,25 +155,25 @@ impl From<VarNum> for i8 {
 
 impl TlvEncode for VarNum {
     fn encode(&self) -> Bytes {
-        let mut bytes = BytesMut::with_capacity(self.size());
+        encode_to_bytes(self)
+    }
 
+    fn encode_to(&self, buf: &mut impl BufMut) {
         match self.value() {
-            0x00..=0xFC => bytes.put_u8(self.value() as u8),
+            0x00..=0xFC => buf.put_u8(self.value() as u8),
             0xFD..=0xFFFF => {
-                bytes.put_u8(0xFD);
-                bytes.put_u16(self.value() as u16);
+                buf.put_u8(0xFD);
+                buf.put_u16(self.value() as u16);
             }
             0x10000..=0xFFFF_FFFF => {
-                bytes.put_u8(0xFE);
-                bytes.put_u32(self.value() as u32);
+                buf.put_u8(0xFE);
+                buf.put_u32(self.value() as u32);
             }
             _ => {
-                bytes.put_u8(0xFF);
-                bytes.put_u64(self.value() as u64);
+                buf.put_u8(0xFF);
+                buf.put_u64(self.value());
             }
         }
-
-        bytes.freeze()
     }
 
     fn size(&self) -> usize {
@@ -176,11 +188,28 @@ impl TlvEncode for VarNum {
 
 impl TlvDecode for VarNum {
     fn decode(bytes: &mut Bytes) -> Result<Self> {
-        if bytes.remaining() <= 0 {
+        Self::decode_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_with(bytes: &mut Bytes, config: &DecodeConfig) -> Result<Self> {
+        let mut cur = &bytes[..];
+        let value = Self::decode_ref_with(&mut cur, config)?;
+        bytes.advance(bytes.len() - cur.len());
+        Ok(value)
+    }
+}
+
+impl<'a> TlvDecodeRef<'a> for VarNum {
+    fn decode_ref(bytes: &mut &'a [u8]) -> Result<Self> {
+        Self::decode_ref_with(bytes, &DecodeConfig::default())
+    }
+
+    fn decode_ref_with(bytes: &mut &'a [u8], config: &DecodeConfig) -> Result<Self> {
+        if !bytes.has_remaining() {
             return Err(TlvError::UnexpectedEndOfStream);
         }
         let first = bytes.get_u8();
-        Ok(match first {
+        let value: Self = match first {
             0x00..=0xFC => first.into(),
             0xFD => {
                 if bytes.remaining() < 2 {
@@ -200,7 +229,11 @@ impl TlvDecode for VarNum {
                 }
                 bytes.get_u64().into()
             }
-        })
+        };
+        if config.strict() && value.size() != Self::encoded_size(first) {
+            return Err(TlvError::NonMinimalEncoding);
+        }
+        Ok(value)
     }
 }
 
@@ -208,6 +241,11 @@ impl TlvDecode for VarNum {
 mod tests {
     use super::*;
 
+    fn decode_strict(data: &'static [u8]) -> Result<u64> {
+        let strict = DecodeConfig::new().with_strict(true);
+        VarNum::decode_with(&mut Bytes::from_static(data), &strict).map(|num| num.value())
+    }
+
     #[test]
     fn simple_number() {
         let num = VarNum::from(5u8);
@@ -264,4 +302,81 @@ mod tests {
             0xFFFF_FFFF_FFFF_FFFF
         );
     }
+
+    #[test]
+    fn decode_ref() {
+        let data = [0xFD, 0x01, 0x00, 0xFE, 0x00, 0x01, 0x00, 0x00, 0x05];
+        let mut slice = &data[..];
+        assert_eq!(VarNum::decode_ref(&mut slice).unwrap().value(), 0x100);
+        assert_eq!(VarNum::decode_ref(&mut slice).unwrap().value(), 0x10000);
+        assert_eq!(VarNum::decode_ref(&mut slice).unwrap().value(), 5);
+        assert_eq!(slice.len(), 0);
+        assert_eq!(
+            VarNum::decode_ref(&mut &[0xFE, 0x00][..]).unwrap_err(),
+            TlvError::UnexpectedEndOfStream
+        );
+    }
+
+    #[test]
+    fn lenient_non_minimal() {
+        let mut data = Bytes::from_static(&[0xFD, 0x00, 0x05]);
+        assert_eq!(VarNum::decode(&mut data).unwrap().value(), 5);
+    }
+
+    #[test]
+    fn strict_number1() {
+        assert_eq!(decode_strict(&[0x00]), Ok(0));
+        assert_eq!(decode_strict(&[0xFC]), Ok(0xFC));
+    }
+
+    #[test]
+    fn strict_number3() {
+        assert_eq!(
+            decode_strict(&[0xFD, 0x00, 0x00]),
+            Err(TlvError::NonMinimalEncoding)
+        );
+        assert_eq!(
+            decode_strict(&[0xFD, 0x00, 0xFC]),
+            Err(TlvError::NonMinimalEncoding)
+        );
+        assert_eq!(decode_strict(&[0xFD, 0x00, 0xFD]), Ok(0xFD));
+        assert_eq!(decode_strict(&[0xFD, 0xFF, 0xFF]), Ok(0xFFFF));
+    }
+
+    #[test]
+    fn strict_number5() {
+        assert_eq!(
+            decode_strict(&[0xFE, 0x00, 0x00, 0x00, 0xFC]),
+            Err(TlvError::NonMinimalEncoding)
+        );
+        assert_eq!(
+            decode_strict(&[0xFE, 0x00, 0x00, 0xFF, 0xFF]),
+            Err(TlvError::NonMinimalEncoding)
+        );
+        assert_eq!(decode_strict(&[0xFE, 0x00, 0x01, 0x00, 0x00]), Ok(0x10000));
+        assert_eq!(
+            decode_strict(&[0xFE, 0xFF, 0xFF, 0xFF, 0xFF]),
+            Ok(0xFFFF_FFFF)
+        );
+    }
+
+    #[test]
+    fn strict_number9() {
+        assert_eq!(
+            decode_strict(&[0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF]),
+            Err(TlvError::NonMinimalEncoding)
+        );
+        assert_eq!(
+            decode_strict(&[0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF]),
+            Err(TlvError::NonMinimalEncoding)
+        );
+        assert_eq!(
+            decode_strict(&[0xFF, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00]),
+            Ok(0x1_0000_0000)
+        );
+        assert_eq!(
+            decode_strict(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]),
+            Ok(0xFFFF_FFFF_FFFF_FFFF)
+        );
+    }
 }